@@ -1,10 +1,21 @@
-use move_binding::move_codegen::MoveCodegen;
-use move_binding::SuiNetwork;
+use move_binding::error::Error;
+use move_binding::lockfile::Lockfile;
+use move_binding::move_codegen::{CodegenOptions, Dependency, MoveCodegen};
+use move_binding::package_cache::PackageCache;
+use move_binding::package_provider::{
+    Backend, LocalModuleProvider, ModuleProvider, MoveModuleProvider,
+};
+use move_binding::{SuiNetwork, ENV_OVERRIDES};
 use proc_macro::TokenStream;
-use proc_macro2::Ident;
+use proc_macro2::{Ident, Span};
 use quote::quote;
+use std::path::PathBuf;
+use std::str::FromStr;
 use syn::parse::{Parse, ParseStream};
-use syn::{parse_macro_input, DeriveInput, ExprPath, GenericParam, Generics, LitStr, Token};
+use syn::{
+    bracketed, parse_macro_input, DeriveInput, ExprPath, GenericParam, Generics, LitBool, LitInt,
+    LitStr, Token,
+};
 
 #[proc_macro_derive(Key)]
 pub fn key_derive(input: TokenStream) -> TokenStream {
@@ -86,17 +97,68 @@ pub fn move_struct_derive(input: TokenStream) -> TokenStream {
 
 struct MoveContractArgs {
     network: SuiNetwork,
+    backend: Backend,
     package_alias: String,
     package: String,
-    path: Option<String>,
+    version: Option<u64>,
+    base_path: Option<String>,
+    build_path: Option<String>,
+    lockfile: Option<String>,
+    options: CodegenOptions,
+    spans: ArgSpans,
+}
+
+/// Spans of the macro arguments, used to point errors at the offending argument.
+#[derive(Default)]
+struct ArgSpans {
+    package: Option<Span>,
+    version: Option<Span>,
+    path: Option<Span>,
+    network: Option<Span>,
+    deps: Option<Span>,
+    lockfile: Option<Span>,
+}
+
+impl ArgSpans {
+    fn error_span(&self, error: &Error) -> Span {
+        let span = match error {
+            Error::Network { .. }
+            | Error::MalformedResponse { .. }
+            | Error::UnknownNetwork(_)
+            | Error::UnknownBackend(_)
+            | Error::BackendUnavailable { .. } => self.network.or(self.package),
+            Error::InvalidVersion(_) => self.version.or(self.package),
+            Error::Io { .. } => self.path,
+            Error::PackageNotFound(_)
+            | Error::InvalidPackage { .. }
+            | Error::MvrNameUnresolved { .. }
+            | Error::BytecodeDeserialization { .. }
+            | Error::UnsupportedType { .. } => self.package.or(self.path),
+            Error::MissingDependency { .. } | Error::InvalidDependency { .. } => {
+                self.deps.or(self.package)
+            }
+            Error::Lockfile(_) => self.lockfile,
+            Error::Cache(_) | Error::Codegen { .. } => None,
+        };
+        span.unwrap_or_else(Span::call_site)
+    }
 }
 
 impl Parse for MoveContractArgs {
     fn parse(input: ParseStream) -> Result<Self, syn::Error> {
         let mut alias = None;
         let mut package = None;
-        let mut path = None;
+        let mut version = None;
+        let mut base_path = None;
+        let mut build_path = None;
+        let mut lockfile = None;
         let mut network = SuiNetwork::Mainnet;
+        let mut gql_url = None;
+        let mut mvr_url = None;
+        let mut rpc_url = None;
+        let mut backend = Backend::default();
+        let mut options = CodegenOptions::default();
+        let mut spans = ArgSpans::default();
 
         while !input.is_empty() {
             let key: Ident = input.parse()?; // Parse the key (e.g., alias, package, deps)
@@ -105,23 +167,69 @@ impl Parse for MoveContractArgs {
             if key == "alias" {
                 alias = Some(input.parse::<LitStr>()?.value()); // Parse string literal
             } else if key == "package" {
-                package = Some(input.parse::<LitStr>()?.value()); // Parse string literal
+                let lit = input.parse::<LitStr>()?;
+                spans.package = Some(lit.span());
+                package = Some(lit.value());
+            } else if key == "version" {
+                let lit = input.parse::<LitInt>()?;
+                spans.version = Some(lit.span());
+                version = Some(lit.base10_parse::<u64>()?);
+            } else if key == "include_package_private" {
+                options.include_package_private = input.parse::<LitBool>()?.value;
+            } else if key == "include_deps" {
+                options.include_deps = input.parse::<LitBool>()?.value;
             } else if key == "base_path" {
                 let p = input.parse::<ExprPath>()?.path;
-                path = Some(quote!(#p).to_string()); // Parse string literal
-            } else if key == "network" {
-                if let Ok(lit) = input.parse::<LitStr>() {
-                    network = match lit.value().to_lowercase().as_str() {
-                        "mainnet" => SuiNetwork::Mainnet,
-                        "testnet" => SuiNetwork::Testnet,
-                        _ => {
-                            return Err(syn::Error::new(
-                                key.span(),
-                                "Unknown network, only ['mainnet', 'testnet'] are supported.",
-                            ))
-                        }
+                base_path = Some(quote!(#p).to_string()); // Parse string literal
+            } else if key == "deps" {
+                spans.deps = Some(key.span());
+                let content;
+                bracketed!(content in input);
+                while !content.is_empty() {
+                    let path = content.parse::<ExprPath>()?.path;
+                    let package = if content.peek(Token![=]) {
+                        content.parse::<Token![=]>()?;
+                        Some(content.parse::<LitStr>()?.value())
+                    } else {
+                        None
                     };
+                    options.deps.push(Dependency {
+                        path: quote!(#path).to_string().replace(' ', ""),
+                        package,
+                    });
+                    if content.peek(Token![,]) {
+                        content.parse::<Token![,]>()?;
+                    }
                 }
+            } else if key == "path" {
+                let lit = input.parse::<LitStr>()?;
+                spans.path = Some(lit.span());
+                build_path = Some(lit.value());
+            } else if key == "lockfile" {
+                let lit = input.parse::<LitStr>()?;
+                spans.lockfile = Some(lit.span());
+                lockfile = Some(lit.value());
+            } else if key == "network" {
+                let lit = input.parse::<LitStr>()?;
+                spans.network = Some(lit.span());
+                network = SuiNetwork::from_str(&lit.value())
+                    .map_err(|e| syn::Error::new(lit.span(), e))?;
+            } else if key == "gql_url" {
+                let lit = input.parse::<LitStr>()?;
+                spans.network = Some(lit.span());
+                gql_url = Some(lit.value());
+            } else if key == "mvr_url" {
+                let lit = input.parse::<LitStr>()?;
+                spans.network = Some(lit.span());
+                mvr_url = Some(lit.value());
+            } else if key == "rpc_url" {
+                let lit = input.parse::<LitStr>()?;
+                spans.network = Some(lit.span());
+                rpc_url = Some(lit.value());
+            } else if key == "backend" {
+                let lit = input.parse::<LitStr>()?;
+                backend =
+                    Backend::from_str(&lit.value()).map_err(|e| syn::Error::new(lit.span(), e))?;
             } else {
                 return Err(syn::Error::new(key.span(), "Unknown key"));
             }
@@ -131,29 +239,100 @@ impl Parse for MoveContractArgs {
             }
         }
 
+        let network = network
+            .with_endpoints(gql_url, mvr_url, rpc_url)
+            .with_env_overrides()
+            .map_err(|e| syn::Error::new(spans.error_span(&e), e))?;
+
+        // Packages read from a local build directory default to the root package of the build.
+        let package = match (package, &build_path) {
+            (Some(package), _) => package,
+            (None, Some(_)) => String::new(),
+            (None, None) => return Err(syn::Error::new(input.span(), "Missing package")),
+        };
+
         Ok(MoveContractArgs {
             network,
+            backend,
             package_alias: alias.ok_or_else(|| syn::Error::new(input.span(), "Missing alias"))?,
-            package: package.ok_or_else(|| syn::Error::new(input.span(), "Missing package"))?,
-            path,
+            package,
+            version,
+            base_path,
+            build_path,
+            lockfile,
+            options,
+            spans,
         })
     }
 }
 
+/// Generates the bindings of a Move package, see the README for its arguments.
 #[proc_macro]
 pub fn move_contract(input: TokenStream) -> TokenStream {
     let MoveContractArgs {
         network,
+        backend,
         package_alias,
         package,
-        path,
+        version,
+        base_path,
+        build_path,
+        lockfile,
+        options,
+        spans,
     } = parse_macro_input!(input as MoveContractArgs);
-    MoveCodegen::expand(
-        network,
+
+    // Relative paths are resolved against the crate invoking the macro.
+    let manifest_dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default());
+    let local_provider = build_path.map(|path| LocalModuleProvider::new(manifest_dir.join(path)));
+    let network_provider;
+    let module_provider: &dyn ModuleProvider = match &local_provider {
+        Some(local_provider) => local_provider,
+        None => {
+            let mut provider = MoveModuleProvider::new(network)
+                .with_backend(backend)
+                .with_cache(PackageCache::from_env());
+            if let Some(lockfile) = lockfile {
+                provider = provider.with_lockfile(Lockfile::new(manifest_dir.join(lockfile)));
+            }
+            network_provider = provider;
+            &network_provider
+        }
+    };
+
+    // Reading the overrides with `option_env!` records them in the dep-info of the crate, so
+    // that cargo expands the macro again when they change.
+    let env_count = ENV_OVERRIDES.len();
+    let env_overrides = ENV_OVERRIDES
+        .iter()
+        .map(|name| LitStr::new(name, Span::call_site()));
+    let tracked_env = quote! {
+        const _: [Option<&str>; #env_count] = [#(option_env!(#env_overrides)),*];
+    };
+
+    let expanded = MoveCodegen::expand_with_provider(
+        module_provider,
         &package,
+        version,
         &package_alias,
-        &path.unwrap_or("crate".to_string()),
+        &base_path.unwrap_or("crate".to_string()),
+        &options,
     )
-    .unwrap()
+    .unwrap_or_else(|e| syn::Error::new(spans.error_span(&e), e).to_compile_error());
+
+    // Likewise, including the files read from a local build directory expands the macro again
+    // when the Move package is rebuilt.
+    let tracked_files = local_provider
+        .iter()
+        .flat_map(LocalModuleProvider::read_files)
+        .map(|path| {
+            let path = path.display().to_string();
+            quote! { const _: &[u8] = include_bytes!(#path); }
+        });
+    quote! {
+        #expanded
+        #tracked_env
+        #(#tracked_files)*
+    }
     .into()
 }
@@ -1,35 +1,37 @@
 use crate::bridge::bridge::BridgeInner;
 use crate::sui::dynamic_field::Field;
 use move_binding_derive::move_contract;
+use move_types::builder::CheckedBuilder;
+use move_types::functions::Ref;
 use move_types::Key;
 use std::str::FromStr;
 use sui_client::Client;
-use sui_sdk_types::{Address, ObjectData, ObjectId};
+use sui_sdk_types::{Address, Argument, ObjectData, ObjectId};
 use sui_transaction_builder::unresolved::Input;
-use sui_transaction_builder::TransactionBuilder;
+use sui_transaction_builder::{Serialized, TransactionBuilder};
 
 move_contract! {alias = "move_lib", package = "0x1"}
-move_contract! {alias = "sui", package = "0x2"}
-move_contract! {alias = "sui_system", package = "0x3"}
-move_contract! {alias = "bridge", package = "0xb"}
+move_contract! {alias = "sui", package = "0x2", deps = [crate::move_lib]}
+move_contract! {alias = "sui_system", package = "0x3", deps = [crate::move_lib, crate::sui]}
+move_contract! {alias = "bridge", package = "0xb", deps = [crate::move_lib, crate::sui, crate::sui_system]}
 
-move_contract! {alias = "mvr_metadata", package = "@mvr/metadata"}
-move_contract! {alias = "suins", package = "0xd22b24490e0bae52676651b4f56660a5ff8022a2576e0089f79b3c88d44e08f0"}
-move_contract! {alias = "mvr_core", package = "@mvr/core"}
+move_contract! {alias = "mvr_metadata", package = "@mvr/metadata", deps = [crate::move_lib, crate::sui]}
+move_contract! {alias = "suins", package = "0xd22b24490e0bae52676651b4f56660a5ff8022a2576e0089f79b3c88d44e08f0", deps = [crate::move_lib, crate::sui]}
+move_contract! {alias = "mvr_core", package = "@mvr/core", deps = [crate::move_lib, crate::sui, crate::suins = "0xd22b24490e0bae52676651b4f56660a5ff8022a2576e0089f79b3c88d44e08f0", crate::mvr_metadata = "@mvr/metadata"]}
 
-move_contract! {alias = "token", package = "0xdeeb7a4662eec9f2f3def03fb937a663dddaa2e215b8078a284d026b7946c270"}
+move_contract! {alias = "token", package = "0xdeeb7a4662eec9f2f3def03fb937a663dddaa2e215b8078a284d026b7946c270", deps = [crate::move_lib, crate::sui]}
 
-move_contract! {alias = "deepbook", package = "@deepbook/core"}
+move_contract! {alias = "deepbook", package = "@deepbook/core", deps = [crate::move_lib, crate::sui, crate::token = "0xdeeb7a4662eec9f2f3def03fb937a663dddaa2e215b8078a284d026b7946c270"]}
 
-move_contract! {alias = "tokenv2", package = "0x36dbef866a1d62bf7328989a10fb2f07d769f4ee587c0de4a0a256e57e0a58a8", network="testnet"}
-move_contract! {alias = "deepbookv2", package = "@deepbook/core", network = "testnet"}
+move_contract! {alias = "tokenv2", package = "0x36dbef866a1d62bf7328989a10fb2f07d769f4ee587c0de4a0a256e57e0a58a8", network="testnet", deps = [crate::move_lib, crate::sui]}
+move_contract! {alias = "deepbookv2", package = "@deepbook/core", network = "testnet", deps = [crate::move_lib, crate::sui, crate::tokenv2 = "0x36dbef866a1d62bf7328989a10fb2f07d769f4ee587c0de4a0a256e57e0a58a8"]}
 
-//move_contract! {alias = "mvr_metadata_testnet", package = "@mvr/metadata", network = "testnet", deps = [crate::sui]}
+move_contract! {alias = "mvr_metadata_testnet", package = "@mvr/metadata", network = "testnet", deps = [crate::move_lib, crate::sui]}
 
-move_contract! {alias = "commander", package = "0xdc931e30acc15dbc7fcbd39cd385a03894a7236761490ff4d5b9dbf51af3ce26", network="testnet"}
+move_contract! {alias = "commander", package = "0xdc931e30acc15dbc7fcbd39cd385a03894a7236761490ff4d5b9dbf51af3ce26", network="testnet", deps = [crate::move_lib, crate::sui]}
 
-move_contract! {alias = "wal", package = "0x8270feb7375eee355e64fdb69c50abb6b5f9393a722883c1cf45f8e26048810a", network="testnet"}
-move_contract! {alias = "walrus", package = "0xd84704c17fc870b8764832c535aa6b11f21a95cd6f5bb38a9b07d2cf42220c66", network="testnet"}
+move_contract! {alias = "wal", package = "0x8270feb7375eee355e64fdb69c50abb6b5f9393a722883c1cf45f8e26048810a", network="testnet", deps = [crate::move_lib, crate::sui]}
+move_contract! {alias = "walrus", package = "0xd84704c17fc870b8764832c535aa6b11f21a95cd6f5bb38a9b07d2cf42220c66", network="testnet", deps = [crate::move_lib, crate::sui, crate::wal = "0x8270feb7375eee355e64fdb69c50abb6b5f9393a722883c1cf45f8e26048810a"]}
 
 #[tokio::test]
 pub async fn test_deserialize_object() {
@@ -50,9 +52,109 @@ pub async fn test_deserialize_object() {
         let bridge: Field<u64, BridgeInner> = bcs::from_bytes(o.contents()).unwrap();
         println!("{:?}", bridge);
         println!("{:?}", bridge.id());
+
+        let object = sui::decode_object(o.object_type(), o.contents()).unwrap();
+        let Some(sui::AnyObject::DynamicField(sui::dynamic_field::Object::Field(field))) = object
+        else {
+            panic!("expected a dynamic field, got {object:?}");
+        };
+        let decoded = field.decode::<Field<u64, BridgeInner>>().unwrap().unwrap();
+        assert_eq!(decoded.id(), bridge.id());
+        assert!(field.decode::<Field<u64, u64>>().is_none());
     }
 }
 
+#[test]
+pub fn test_decode_event() {
+    use move_types::MoveStruct;
+    let type_ = sui_system::validator::StakingRequestEvent::struct_type();
+    let bytes = bcs::to_bytes(&(
+        ObjectId::new([1; 32]),
+        Address::TWO,
+        Address::TWO,
+        1u64,
+        1_000_000_000u64,
+    ))
+    .unwrap();
+
+    let event = sui_system::Event::try_from_type_and_bcs(&type_, &bytes).unwrap();
+    assert!(matches!(
+        event,
+        Some(sui_system::Event::Validator(
+            sui_system::validator::Event::StakingRequestEvent(e)
+        )) if e.amount == 1_000_000_000
+    ));
+    // Events of other packages are left to their own bindings.
+    assert!(sui::Event::try_from_type_and_bcs(&type_, &bytes)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+pub fn test_construct_structs() {
+    use move_types::MoveStruct;
+    use sui::balance::Balance;
+    use sui::sui::SUI;
+    use sui_system::validator::StakingRequestEvent;
+
+    let balance = Balance::<SUI>::new(100);
+    assert_eq!(*balance.value(), 100);
+    let bytes = bcs::to_bytes(&balance).unwrap();
+    let decoded: Balance<SUI> = bcs::from_bytes(&bytes).unwrap();
+    assert_eq!(*decoded.value(), 100);
+
+    let builder = StakingRequestEvent::builder()
+        .pool_id(ObjectId::new([1; 32]))
+        .validator_address(Address::TWO)
+        .staker_address(Address::TWO)
+        .epoch(1);
+    assert!(matches!(
+        StakingRequestEvent::builder().build(),
+        Err(move_types::error::BindingError::MissingField {
+            field: "pool_id",
+            ..
+        })
+    ));
+    let event = builder.amount(1_000_000_000).build().unwrap();
+    let bytes = bcs::to_bytes(&event).unwrap();
+    assert!(matches!(
+        sui_system::Event::try_from_type_and_bcs(&StakingRequestEvent::struct_type(), &bytes),
+        Ok(Some(sui_system::Event::Validator(
+            sui_system::validator::Event::StakingRequestEvent(e)
+        ))) if e.amount == 1_000_000_000
+    ));
+}
+
+#[test]
+pub fn test_decode_object() {
+    use move_types::MoveStruct;
+    let type_ = sui::clock::Clock::struct_type();
+    let bytes = bcs::to_bytes(&(ObjectId::new([6; 32]), 1_700_000_000_000u64)).unwrap();
+
+    let object = sui::decode_object(&type_, &bytes).unwrap();
+    assert!(matches!(
+        object,
+        Some(sui::AnyObject::Clock(sui::clock::Object::Clock(clock)))
+            if clock.timestamp_ms == 1_700_000_000_000
+    ));
+    // Objects of other packages are left to their own bindings.
+    let type_ = sui_system::sui_system::SuiSystemState::struct_type();
+    assert!(sui::decode_object(&type_, &bytes).unwrap().is_none());
+
+    // `Coin<SUI>` is referenced by `sui::sui::transfer`, other coins are kept as BCS.
+    let type_ = sui::coin::Coin::<sui::sui::SUI>::struct_type();
+    let bytes = bcs::to_bytes(&(ObjectId::new([7; 32]), 1_000u64)).unwrap();
+    assert!(matches!(
+        sui::decode_object(&type_, &bytes).unwrap(),
+        Some(sui::AnyObject::Coin(sui::coin::Object::CoinSUI(coin))) if coin.balance.value == 1_000
+    ));
+    let type_ = sui::coin::Coin::<u64>::struct_type();
+    assert!(matches!(
+        sui::decode_object(&type_, &bytes).unwrap(),
+        Some(sui::AnyObject::Coin(sui::coin::Object::Coin(coin))) if coin.type_ == type_
+    ));
+}
+
 #[tokio::test]
 pub async fn test_function_call() {
     let client = Client::new("https://sui-mainnet.mystenlabs.com/graphql").unwrap();
@@ -86,12 +188,101 @@ pub async fn test_function_call() {
     );
     sui::transfer::public_transfer(&mut builder, new_bag, owner.into());
 
+    // Shared objects are passed by their initial shared version.
+    let clock = ObjectId::from_str("0x6").unwrap();
+    sui::clock::timestamp_ms(&mut builder, Ref::shared(clock, 1, false));
+
+    let tx = builder.finish().unwrap();
+    let result = client.dry_run_tx(&tx, None).await.unwrap();
+
+    println!("{:?}", result);
+}
+
+#[tokio::test]
+pub async fn test_split_coins() {
+    use move_types::coin;
+    use sui::coin::Coin;
+    use sui::sui::SUI;
+
+    let client = Client::new("https://sui-mainnet.mystenlabs.com/graphql").unwrap();
+
+    let owner = Address::from_str("0x2").unwrap();
+    let gas =
+        ObjectId::from_str("0x726b714a3c4c681d8a9b1ff1833ad368585579a273362e1cbd738c0c8f70dabd")
+            .unwrap();
+    let gas = client.object(gas.into(), None).await.unwrap().unwrap();
+
+    let mut builder = TransactionBuilder::new();
+    builder.set_sender(owner);
+    builder.add_gas_objects(vec![Input::owned(
+        gas.object_id(),
+        gas.version(),
+        gas.digest(),
+    )]);
+    builder.set_gas_budget(10000000);
+    builder.set_gas_price(1000);
+
+    let mut gas_coin = coin::gas_coin::<Coin<SUI>>();
+    let mut coins = coin::split_coins(
+        &mut builder,
+        gas_coin.borrow_mut(),
+        vec![1000.into(), 2000.into()],
+    );
+    let second = coins.pop().unwrap();
+    let (merged, split) = coin::merge_and_split(
+        &mut builder,
+        coins.pop().unwrap(),
+        vec![second],
+        vec![500.into()],
+    );
+    sui::transfer::public_transfer(&mut builder, merged, owner.into());
+    for coin in split {
+        sui::transfer::public_transfer(&mut builder, coin, owner.into());
+    }
+
     let tx = builder.finish().unwrap();
     let result = client.dry_run_tx(&tx, None).await.unwrap();
 
     println!("{:?}", result);
 }
 
+#[test]
+pub fn test_checked_builder() {
+    let mut builder = CheckedBuilder::new(TransactionBuilder::new());
+    let (kiosk, cap) = sui::kiosk::new(&mut builder);
+    sui::transfer::public_share_object(&mut builder, kiosk);
+    assert_eq!(builder.unused_results().len(), 1);
+
+    sui::transfer::public_transfer(&mut builder, cap, Address::TWO.into());
+    assert!(builder.unused_results().is_empty());
+
+    // The second cap is never used.
+    let mut builder = CheckedBuilder::new(TransactionBuilder::new());
+    let (kiosk, _cap) = sui::kiosk::new(&mut builder);
+    sui::transfer::public_share_object(&mut builder, kiosk);
+    assert!(matches!(
+        builder.finish(),
+        Err(move_types::error::BindingError::UnusedResults(unused))
+            if unused == [Argument::NestedResult(0, 1)]
+    ));
+}
+
+#[test]
+pub fn test_entry_only_args() {
+    use move_types::functions::EntryArg;
+
+    // Entry-only functions take inputs rather than `Arg`s, results cannot be passed to them.
+    let mut builder = TransactionBuilder::new();
+    let random = ObjectId::from_str("0x8").unwrap();
+    sui::random::update_randomness_state(
+        &mut builder,
+        EntryArg::shared(random, 1, true),
+        1.into(),
+        vec![1u8, 2].into(),
+    );
+    assert_eq!(builder.input(Serialized(&0u64).into()), Argument::Input(3));
+}
+
 #[tokio::test]
 async fn test_deserialize_enum() {
     use commander::history;
@@ -117,3 +308,69 @@ async fn test_deserialize_enum() {
     let deserialized: history::Record = bcs::from_bytes(&bytes).unwrap();
     println!("Deserialized: {:?}", deserialized);
 }
+
+#[tokio::test]
+pub async fn test_multiple_returns() {
+    let client = Client::new("https://sui-mainnet.mystenlabs.com/graphql").unwrap();
+
+    let owner = Address::from_str("0x2").unwrap();
+    let gas =
+        ObjectId::from_str("0x726b714a3c4c681d8a9b1ff1833ad368585579a273362e1cbd738c0c8f70dabd")
+            .unwrap();
+    let gas = client.object(gas.into(), None).await.unwrap().unwrap();
+
+    let mut builder = TransactionBuilder::new();
+    builder.set_sender(owner);
+    builder.add_gas_objects(vec![Input::owned(
+        gas.object_id(),
+        gas.version(),
+        gas.digest(),
+    )]);
+    builder.set_gas_budget(10000000);
+    builder.set_gas_price(1000);
+
+    let (kiosk, cap) = sui::kiosk::new(&mut builder);
+    sui::transfer::public_share_object(&mut builder, kiosk);
+    let (other_kiosk, other_cap) = sui::kiosk::new(&mut builder);
+    sui::transfer::public_share_object(&mut builder, other_kiosk);
+    move_types::functions::transfer_objects(&mut builder, vec![cap, other_cap], owner.into());
+
+    let tx = builder.finish().unwrap();
+    let result = client.dry_run_tx(&tx, None).await.unwrap();
+    assert!(result.error.is_none(), "{:?}", result.error);
+}
+
+#[test]
+pub fn test_multiple_returns_indices() {
+    let mut builder = TransactionBuilder::new();
+    let (kiosk, cap) = sui::kiosk::new(&mut builder);
+    let (other_kiosk, other_cap) = sui::kiosk::new(&mut builder);
+
+    assert!(matches!(
+        Argument::try_from(kiosk),
+        Ok(Argument::NestedResult(0, 0))
+    ));
+    assert!(matches!(
+        Argument::try_from(cap),
+        Ok(Argument::NestedResult(0, 1))
+    ));
+    assert!(matches!(
+        Argument::try_from(other_kiosk),
+        Ok(Argument::NestedResult(1, 0))
+    ));
+    assert!(matches!(
+        Argument::try_from(other_cap),
+        Ok(Argument::NestedResult(1, 1))
+    ));
+}
+
+#[test]
+pub fn test_ability_bounds() {
+    use move_types::functions::{Arg, MutRef};
+
+    // Values with `store` can be added as dynamic fields, see the UI tests of `move-types` for
+    // the values rejected by the ability bounds.
+    let mut builder = TransactionBuilder::new();
+    let uid = MutRef::Resolved(Argument::Input(0));
+    sui::dynamic_field::add(&mut builder, uid, Arg::from(1u64), Arg::from(2u64));
+}
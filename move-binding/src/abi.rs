@@ -0,0 +1,365 @@
+use crate::move_codegen::is_tx_context;
+use crate::package_provider::{FunctionNames, Package};
+use move_binary_format::file_format::Visibility;
+use move_binary_format::normalized::{Enum, Function, Module, Struct, Type};
+use move_core_types::account_address::AccountAddress;
+use move_core_types::identifier::Identifier as MoveIdentifier;
+use move_core_types::u256::U256;
+use serde::ser::{SerializeSeq, Serializer};
+use serde::Serialize;
+use std::str::FromStr;
+use sui_sdk_types::{Address, Argument, Identifier, ObjectId, TypeTag};
+use sui_transaction_builder::unresolved::Input;
+use sui_transaction_builder::{Function as MoveFunction, Serialized, TransactionBuilder};
+
+/// Errors raised when building calls with a [`PackageAbi`].
+#[derive(Debug, thiserror::Error)]
+pub enum AbiError {
+    #[error("Function [{module}::{function}] not found")]
+    FunctionNotFound { module: String, function: String },
+    #[error("Invalid call to [{function}]: {reason}")]
+    InvalidCall { function: String, reason: String },
+}
+
+/// Runtime view of a package, to introspect its modules and build calls to its functions
+/// without generating bindings at compile time.
+pub struct PackageAbi {
+    package: Package,
+}
+
+impl PackageAbi {
+    pub fn new(package: Package) -> Self {
+        Self { package }
+    }
+
+    pub fn version(&self) -> u64 {
+        self.package.version
+    }
+
+    pub fn modules(&self) -> impl Iterator<Item = &Module> {
+        self.package.module_map.values()
+    }
+
+    pub fn module(&self, module: &str) -> Option<&Module> {
+        self.package.module_map.get(module)
+    }
+
+    pub fn functions(&self, module: &str) -> impl Iterator<Item = (&str, &Function)> {
+        self.module(module)
+            .into_iter()
+            .flat_map(|m| m.functions.iter().map(|(name, f)| (name.as_str(), f)))
+    }
+
+    pub fn function(&self, module: &str, function: &str) -> Option<&Function> {
+        self.module(module)?
+            .functions
+            .iter()
+            .find(|(name, _)| name.as_str() == function)
+            .map(|(_, f)| f)
+    }
+
+    pub fn struct_(&self, module: &str, name: &str) -> Option<&Struct> {
+        self.module(module)?
+            .structs
+            .iter()
+            .find(|(n, _)| n.as_str() == name)
+            .map(|(_, s)| s)
+    }
+
+    pub fn enum_(&self, module: &str, name: &str) -> Option<&Enum> {
+        self.module(module)?
+            .enums
+            .iter()
+            .find(|(n, _)| n.as_str() == name)
+            .map(|(_, e)| e)
+    }
+
+    /// Source names of the parameters of `function`, only known for packages shipping source
+    /// maps.
+    pub fn parameter_names(&self, module: &str, function: &str) -> Option<&[String]> {
+        let names: &FunctionNames = self
+            .package
+            .source_names
+            .get(module)?
+            .functions
+            .get(function)?;
+        Some(&names.parameters)
+    }
+
+    /// Adds a call to `module::function` to `builder`, after checking `type_args` and `args`
+    /// against the signature of the function. The `TxContext` parameter, if any, is provided by
+    /// the runtime and must not be part of `args`.
+    pub fn move_call(
+        &self,
+        builder: &mut TransactionBuilder,
+        module: &str,
+        function: &str,
+        type_args: Vec<TypeTag>,
+        args: Vec<DynamicArg>,
+    ) -> Result<Argument, AbiError> {
+        let name = format!("{module}::{function}");
+        let invalid = |reason: String| AbiError::InvalidCall {
+            function: name.clone(),
+            reason,
+        };
+
+        let fun = self
+            .function(module, function)
+            .ok_or_else(|| AbiError::FunctionNotFound {
+                module: module.to_string(),
+                function: function.to_string(),
+            })?;
+        if fun.visibility != Visibility::Public && !fun.is_entry {
+            return Err(invalid(
+                "only public and entry functions can be called from a transaction".to_string(),
+            ));
+        }
+        if type_args.len() != fun.type_parameters.len() {
+            return Err(invalid(format!(
+                "expected {} type arguments, got {}",
+                fun.type_parameters.len(),
+                type_args.len()
+            )));
+        }
+
+        // Parameters are checked once instantiated with the type arguments of the call.
+        let type_params = type_args
+            .iter()
+            .map(to_type)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(invalid)?;
+        let parameters = fun
+            .parameters
+            .iter()
+            .filter(|t| !is_tx_context(t))
+            .map(|t| substitute(t, &type_params))
+            .collect::<Vec<_>>();
+        if args.len() != parameters.len() {
+            return Err(invalid(format!(
+                "expected {} arguments, got {}",
+                parameters.len(),
+                args.len()
+            )));
+        }
+        for (i, (arg, param)) in args.iter().zip(&parameters).enumerate() {
+            arg.check(param)
+                .map_err(|reason| invalid(format!("argument {i}: {reason}")))?;
+        }
+
+        // Calls are made to the fetched version of the package, which may define functions
+        // missing from its first version.
+        let address = Address::new(self.package.package_id.into_bytes());
+        let module_ident = Identifier::from_str(module).map_err(|e| invalid(e.to_string()))?;
+        let function_ident = Identifier::from_str(function).map_err(|e| invalid(e.to_string()))?;
+        let args = args.into_iter().map(|arg| arg.resolve(builder)).collect();
+        Ok(builder.move_call(
+            MoveFunction::new(address, module_ident, function_ident, type_args),
+            args,
+        ))
+    }
+}
+
+/// Argument of a call built with [`PackageAbi::move_call`].
+pub enum DynamicArg {
+    /// Argument already part of the transaction, e.g. the result of a previous call. Its type
+    /// is not known, and so not checked.
+    Argument(Argument),
+    /// Object input, accepted for parameters of struct types. The type of the object is checked
+    /// when the transaction is executed.
+    Object(Input),
+    /// Pure value, checked against the parameter type.
+    Pure(PureValue),
+}
+
+impl DynamicArg {
+    fn check(&self, param: &Type) -> Result<(), String> {
+        match self {
+            DynamicArg::Argument(_) => Ok(()),
+            DynamicArg::Object(_) => match param {
+                Type::Struct { .. } | Type::TypeParameter(_) => Ok(()),
+                Type::Reference(t) | Type::MutableReference(t)
+                    if matches!(**t, Type::Struct { .. } | Type::TypeParameter(_)) =>
+                {
+                    Ok(())
+                }
+                _ => Err(format!("expected a value of type {param}, got an object")),
+            },
+            DynamicArg::Pure(value) => match param {
+                Type::Reference(t) => value.check(t),
+                _ => value.check(param),
+            },
+        }
+    }
+
+    fn resolve(self, builder: &mut TransactionBuilder) -> Argument {
+        match self {
+            DynamicArg::Argument(arg) => arg,
+            DynamicArg::Object(input) => builder.input(input),
+            DynamicArg::Pure(value) => builder.input(Serialized(&value).into()),
+        }
+    }
+}
+
+impl From<Argument> for DynamicArg {
+    fn from(value: Argument) -> Self {
+        DynamicArg::Argument(value)
+    }
+}
+
+impl From<Input> for DynamicArg {
+    fn from(value: Input) -> Self {
+        DynamicArg::Object(value)
+    }
+}
+
+impl From<PureValue> for DynamicArg {
+    fn from(value: PureValue) -> Self {
+        DynamicArg::Pure(value)
+    }
+}
+
+/// Values that can be passed as pure arguments.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PureValue {
+    Bool(bool),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    U256(U256),
+    Address(Address),
+    /// `0x2::object::ID`.
+    Id(ObjectId),
+    /// `0x1::string::String` or `0x1::ascii::String`.
+    String(String),
+    Vector(Vec<PureValue>),
+    /// `0x1::option::Option`.
+    Option(Option<Box<PureValue>>),
+}
+
+impl PureValue {
+    fn check(&self, type_: &Type) -> Result<(), String> {
+        let matches = match (self, type_) {
+            (PureValue::Bool(_), Type::Bool)
+            | (PureValue::U8(_), Type::U8)
+            | (PureValue::U16(_), Type::U16)
+            | (PureValue::U32(_), Type::U32)
+            | (PureValue::U64(_), Type::U64)
+            | (PureValue::U128(_), Type::U128)
+            | (PureValue::U256(_), Type::U256)
+            | (PureValue::Address(_), Type::Address) => true,
+            (PureValue::Id(_), t) => is_struct(t, AccountAddress::TWO, "object", "ID"),
+            (PureValue::String(_), t) => {
+                is_struct(t, AccountAddress::ONE, "string", "String")
+                    || is_struct(t, AccountAddress::ONE, "ascii", "String")
+            }
+            (PureValue::Vector(values), Type::Vector(t)) => {
+                return values.iter().try_for_each(|value| value.check(t));
+            }
+            (PureValue::Option(value), t)
+                if is_struct(t, AccountAddress::ONE, "option", "Option") =>
+            {
+                return match (value, t) {
+                    (Some(value), Type::Struct { type_arguments, .. }) => {
+                        value.check(&type_arguments[0])
+                    }
+                    _ => Ok(()),
+                };
+            }
+            _ => false,
+        };
+        if matches {
+            Ok(())
+        } else {
+            Err(format!("expected a value of type {type_}, got {self:?}"))
+        }
+    }
+}
+
+impl Serialize for PureValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            PureValue::Bool(v) => v.serialize(serializer),
+            PureValue::U8(v) => v.serialize(serializer),
+            PureValue::U16(v) => v.serialize(serializer),
+            PureValue::U32(v) => v.serialize(serializer),
+            PureValue::U64(v) => v.serialize(serializer),
+            PureValue::U128(v) => v.serialize(serializer),
+            PureValue::U256(v) => v.serialize(serializer),
+            PureValue::Address(v) => v.serialize(serializer),
+            PureValue::Id(v) => v.serialize(serializer),
+            PureValue::String(v) => v.serialize(serializer),
+            PureValue::Vector(values) => {
+                let mut seq = serializer.serialize_seq(Some(values.len()))?;
+                values.iter().try_for_each(|v| seq.serialize_element(v))?;
+                seq.end()
+            }
+            // Move options are vectors of zero or one element.
+            PureValue::Option(value) => {
+                let mut seq = serializer.serialize_seq(Some(value.iter().len()))?;
+                value.iter().try_for_each(|v| seq.serialize_element(v))?;
+                seq.end()
+            }
+        }
+    }
+}
+
+/// Normalized type of a type argument.
+fn to_type(type_: &TypeTag) -> Result<Type, String> {
+    Ok(match type_ {
+        TypeTag::Bool => Type::Bool,
+        TypeTag::U8 => Type::U8,
+        TypeTag::U16 => Type::U16,
+        TypeTag::U32 => Type::U32,
+        TypeTag::U64 => Type::U64,
+        TypeTag::U128 => Type::U128,
+        TypeTag::U256 => Type::U256,
+        TypeTag::Address => Type::Address,
+        TypeTag::Signer => Type::Signer,
+        TypeTag::Vector(t) => Type::Vector(Box::new(to_type(t)?)),
+        TypeTag::Struct(tag) => Type::Struct {
+            address: AccountAddress::new(tag.address.into_inner()),
+            module: MoveIdentifier::new(tag.module.as_str()).map_err(|e| e.to_string())?,
+            name: MoveIdentifier::new(tag.name.as_str()).map_err(|e| e.to_string())?,
+            type_arguments: tag
+                .type_params
+                .iter()
+                .map(to_type)
+                .collect::<Result<_, _>>()?,
+        },
+    })
+}
+
+/// `type_` with its type parameters replaced by `type_args`.
+fn substitute(type_: &Type, type_args: &[Type]) -> Type {
+    match type_ {
+        Type::TypeParameter(index) => type_args[*index as usize].clone(),
+        Type::Vector(t) => Type::Vector(Box::new(substitute(t, type_args))),
+        Type::Reference(t) => Type::Reference(Box::new(substitute(t, type_args))),
+        Type::MutableReference(t) => Type::MutableReference(Box::new(substitute(t, type_args))),
+        Type::Struct {
+            address,
+            module,
+            name,
+            type_arguments,
+        } => Type::Struct {
+            address: *address,
+            module: module.clone(),
+            name: name.clone(),
+            type_arguments: type_arguments
+                .iter()
+                .map(|t| substitute(t, type_args))
+                .collect(),
+        },
+        _ => type_.clone(),
+    }
+}
+
+fn is_struct(type_: &Type, address: AccountAddress, module: &str, name: &str) -> bool {
+    matches!(
+        type_,
+        Type::Struct { address: a, module: m, name: n, .. }
+            if *a == address && m.as_str() == module && n.as_str() == name
+    )
+}
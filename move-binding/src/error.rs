@@ -0,0 +1,73 @@
+use std::path::{Path, PathBuf};
+
+/// Errors raised while fetching packages and generating bindings.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Request to {url} failed: {source}")]
+    Network {
+        url: String,
+        #[source]
+        source: reqwest::Error,
+    },
+    #[error(
+        "Unknown network [{0}], only ['mainnet', 'testnet', 'devnet', 'localnet'] are supported."
+    )]
+    UnknownNetwork(String),
+    #[error("Unknown backend [{0}], only ['graphql', 'jsonrpc'] are supported.")]
+    UnknownBackend(String),
+    #[error("No {backend} endpoint configured for network [{network}]")]
+    BackendUnavailable { backend: String, network: String },
+    #[error("Package {0} not found")]
+    PackageNotFound(String),
+    #[error("Invalid package [{package}]: {reason}")]
+    InvalidPackage { package: String, reason: String },
+    #[error("Invalid version: {0}")]
+    InvalidVersion(String),
+    #[error("Cannot resolve MVR name [{name}]: {reason}")]
+    MvrNameUnresolved { name: String, reason: String },
+    #[error("Malformed response from {url}: {reason}")]
+    MalformedResponse { url: String, reason: String },
+    #[error("Cannot deserialize bytecode of module [{module}]: {reason}")]
+    BytecodeDeserialization { module: String, reason: String },
+    #[error("Unsupported type [{type_}]: {reason}")]
+    UnsupportedType { type_: String, reason: String },
+    #[error("No bindings for the package defining [{type_}], add the package to `deps`")]
+    MissingDependency { type_: String },
+    #[error("Invalid dependency [{path}]: {reason}")]
+    InvalidDependency { path: String, reason: String },
+    #[error("Generated code for [{name}] is invalid: {reason}")]
+    Codegen { name: String, reason: String },
+    #[error("Package cache error: {0}")]
+    Cache(String),
+    #[error("Lockfile error: {0}")]
+    Lockfile(String),
+    #[error("Cannot access {path:?}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+}
+
+impl Error {
+    pub(crate) fn network(url: &str, source: reqwest::Error) -> Self {
+        Self::Network {
+            url: url.to_string(),
+            source,
+        }
+    }
+
+    pub(crate) fn malformed(url: &str, reason: impl ToString) -> Self {
+        Self::MalformedResponse {
+            url: url.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+
+    pub(crate) fn io(path: &Path, source: std::io::Error) -> Self {
+        Self::Io {
+            path: path.to_path_buf(),
+            source,
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use crate::error::Error;
+use crate::package_cache::{Linkage, RawPackage, TypeOrigin};
+use fastcrypto::encoding::{Base64, Encoding};
+use reqwest::header::CONTENT_TYPE;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use sui_sdk_types::Address;
+
+/// BCS content of a package object, as returned by `sui_getObject` with `showBcs`.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawMovePackage {
+    id: Address,
+    #[serde(deserialize_with = "deserialize_u64")]
+    version: u64,
+    module_map: BTreeMap<String, String>,
+    type_origin_table: Vec<RawTypeOrigin>,
+    linkage_table: BTreeMap<Address, RawUpgradeInfo>,
+}
+
+#[derive(Deserialize)]
+struct RawTypeOrigin {
+    module_name: String,
+    #[serde(alias = "struct_name")]
+    datatype_name: String,
+    package: Address,
+}
+
+#[derive(Deserialize)]
+struct RawUpgradeInfo {
+    upgraded_id: Address,
+    #[serde(deserialize_with = "deserialize_u64")]
+    upgraded_version: u64,
+}
+
+/// Sequence numbers are rendered either as numbers or as strings depending on the node version.
+fn deserialize_u64<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    match Value::deserialize(deserializer)? {
+        Value::Number(n) => n
+            .as_u64()
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid version {n}"))),
+        Value::String(s) => s.parse().map_err(serde::de::Error::custom),
+        v => Err(serde::de::Error::custom(format!("invalid version {v}"))),
+    }
+}
+
+/// Fetches the package object `package_id` with `sui_getObject`.
+///
+/// Every version of a package is a distinct object, so `version` can only be checked against
+/// the version of the object, not used to look up another version of the package.
+pub(crate) async fn fetch_package(
+    client: &reqwest::Client,
+    url: &str,
+    package_id: Address,
+    version: Option<u64>,
+) -> Result<RawPackage, Error> {
+    let res = client
+        .post(url)
+        .header(CONTENT_TYPE, "application/json")
+        .json(&json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "sui_getObject",
+            "params": [package_id.to_string(), {"showBcs": true}],
+        }))
+        .send()
+        .await
+        .map_err(|e| Error::network(url, e))?;
+
+    let value = res
+        .json::<Value>()
+        .await
+        .map_err(|e| Error::malformed(url, e))?;
+    if let Some(error) = value.get("error") {
+        return Err(Error::malformed(url, error));
+    }
+    let result = &value["result"];
+    if result.get("error").is_some() || result["data"].is_null() {
+        return Err(Error::PackageNotFound(format!("[{package_id}] at {url}")));
+    }
+    let bcs = &result["data"]["bcs"];
+    if bcs["dataType"] != "package" {
+        return Err(Error::InvalidPackage {
+            package: package_id.to_string(),
+            reason: "object is not a package".to_string(),
+        });
+    }
+    let package: RawMovePackage = serde_json::from_value(bcs.clone())
+        .map_err(|e| Error::malformed(url, format!("invalid package: {e}")))?;
+
+    if let Some(version) = version.filter(|version| *version != package.version) {
+        return Err(Error::InvalidVersion(format!(
+            "package [{package_id}] is at version [{}], not [{version}]; the JSON-RPC backend can \
+             only fetch a version by the id of that version",
+            package.version
+        )));
+    }
+
+    let modules = package
+        .module_map
+        .into_iter()
+        .map(|(name, bytes)| {
+            let bytes = Base64::decode(&bytes)
+                .map_err(|e| Error::malformed(url, format!("invalid module [{name}]: {e}")))?;
+            Ok((name, bytes))
+        })
+        .collect::<Result<_, Error>>()?;
+
+    let type_origins = package
+        .type_origin_table
+        .into_iter()
+        .map(|origin| TypeOrigin {
+            module: origin.module_name,
+            datatype: origin.datatype_name,
+            defining_id: origin.package,
+        })
+        .collect();
+
+    let linkage = package
+        .linkage_table
+        .into_iter()
+        .map(|(original_id, info)| Linkage {
+            original_id,
+            upgraded_id: info.upgraded_id,
+            version: info.upgraded_version,
+        })
+        .collect();
+
+    Ok(RawPackage {
+        package_id: package.id,
+        version: package.version,
+        modules,
+        type_origins,
+        linkage,
+    })
+}
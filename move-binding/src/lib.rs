@@ -1,25 +1,131 @@
+pub mod abi;
+pub mod error;
+mod json_rpc;
+pub mod lockfile;
 pub mod move_codegen;
+pub mod move_value;
+pub mod package_cache;
 mod package_id_resolver;
-mod package_provider;
+pub mod package_provider;
 mod types;
 
-#[derive(Copy, Clone)]
+use crate::error::Error;
+use fastcrypto::encoding::{Encoding, Hex};
+use fastcrypto::hash::{Blake2b256, HashFunction};
+use std::str::FromStr;
+
+/// Environment variables overriding the network at build time, see
+/// [`SuiNetwork::with_env_overrides`].
+pub const ENV_OVERRIDES: [&str; 4] = [
+    "MOVE_BINDING_NETWORK",
+    "MOVE_BINDING_GQL_URL",
+    "MOVE_BINDING_MVR_URL",
+    "MOVE_BINDING_RPC_URL",
+];
+
+#[derive(Clone)]
 pub enum SuiNetwork {
     Mainnet,
     Testnet,
+    Devnet,
+    Localnet,
+    Custom {
+        gql: String,
+        mvr: Option<String>,
+        json_rpc: Option<String>,
+    },
 }
 
 impl SuiNetwork {
-    pub fn mvr_endpoint(&self) -> &str {
+    /// Name of the network, keying its entries in the package cache and the lockfile. Custom
+    /// networks are named after a hash of their endpoints, so that distinct networks never
+    /// share entries.
+    pub fn name(&self) -> String {
+        match self {
+            SuiNetwork::Mainnet => "mainnet".to_string(),
+            SuiNetwork::Testnet => "testnet".to_string(),
+            SuiNetwork::Devnet => "devnet".to_string(),
+            SuiNetwork::Localnet => "localnet".to_string(),
+            SuiNetwork::Custom { gql, mvr, json_rpc } => {
+                let endpoints = [Some(gql), mvr.as_ref(), json_rpc.as_ref()]
+                    .map(|endpoint| endpoint.map(String::as_str).unwrap_or_default())
+                    .join("\n");
+                let digest = Blake2b256::digest(endpoints.as_bytes()).digest;
+                format!("custom-{}", Hex::encode(&digest[..8]))
+            }
+        }
+    }
+    pub fn mvr_endpoint(&self) -> Option<&str> {
         match self {
-            SuiNetwork::Mainnet => "https://mainnet.mvr.mystenlabs.com",
-            SuiNetwork::Testnet => "https://testnet.mvr.mystenlabs.com",
+            SuiNetwork::Mainnet => Some("https://mainnet.mvr.mystenlabs.com"),
+            SuiNetwork::Testnet => Some("https://testnet.mvr.mystenlabs.com"),
+            SuiNetwork::Devnet | SuiNetwork::Localnet => None,
+            SuiNetwork::Custom { mvr, .. } => mvr.as_deref(),
         }
     }
     pub fn gql(&self) -> &str {
         match self {
             SuiNetwork::Mainnet => "https://sui-mainnet.mystenlabs.com/graphql",
             SuiNetwork::Testnet => "https://sui-testnet.mystenlabs.com/graphql",
+            SuiNetwork::Devnet => "https://sui-devnet.mystenlabs.com/graphql",
+            SuiNetwork::Localnet => "http://127.0.0.1:9125/graphql",
+            SuiNetwork::Custom { gql, .. } => gql,
+        }
+    }
+    pub fn json_rpc(&self) -> Option<&str> {
+        match self {
+            SuiNetwork::Mainnet => Some("https://fullnode.mainnet.sui.io:443"),
+            SuiNetwork::Testnet => Some("https://fullnode.testnet.sui.io:443"),
+            SuiNetwork::Devnet => Some("https://fullnode.devnet.sui.io:443"),
+            SuiNetwork::Localnet => Some("http://127.0.0.1:9000"),
+            SuiNetwork::Custom { json_rpc, .. } => json_rpc.as_deref(),
+        }
+    }
+
+    /// Overrides the given endpoints, turning the network into a [`SuiNetwork::Custom`] one
+    /// if any is set.
+    pub fn with_endpoints(
+        self,
+        gql: Option<String>,
+        mvr: Option<String>,
+        json_rpc: Option<String>,
+    ) -> Self {
+        if gql.is_none() && mvr.is_none() && json_rpc.is_none() {
+            return self;
+        }
+        SuiNetwork::Custom {
+            gql: gql.unwrap_or_else(|| self.gql().to_string()),
+            mvr: mvr.or_else(|| self.mvr_endpoint().map(str::to_string)),
+            json_rpc: json_rpc.or_else(|| self.json_rpc().map(str::to_string)),
         }
     }
+
+    /// Applies the build time overrides `MOVE_BINDING_NETWORK`, `MOVE_BINDING_GQL_URL`,
+    /// `MOVE_BINDING_MVR_URL` and `MOVE_BINDING_RPC_URL`, overriding any endpoint turns the
+    /// network into a [`SuiNetwork::Custom`] one. See [`ENV_OVERRIDES`].
+    pub fn with_env_overrides(self) -> Result<Self, Error> {
+        let network = match std::env::var("MOVE_BINDING_NETWORK") {
+            Ok(network) => SuiNetwork::from_str(&network)?,
+            Err(_) => self,
+        };
+        Ok(network.with_endpoints(
+            std::env::var("MOVE_BINDING_GQL_URL").ok(),
+            std::env::var("MOVE_BINDING_MVR_URL").ok(),
+            std::env::var("MOVE_BINDING_RPC_URL").ok(),
+        ))
+    }
+}
+
+impl FromStr for SuiNetwork {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_lowercase().as_str() {
+            "mainnet" => SuiNetwork::Mainnet,
+            "testnet" => SuiNetwork::Testnet,
+            "devnet" => SuiNetwork::Devnet,
+            "localnet" => SuiNetwork::Localnet,
+            _ => return Err(Error::UnknownNetwork(s.to_string())),
+        })
+    }
 }
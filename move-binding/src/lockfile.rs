@@ -0,0 +1,156 @@
+use crate::error::Error;
+use crate::package_cache::RawPackage;
+use crate::SuiNetwork;
+use fastcrypto::encoding::{Encoding, Hex};
+use fastcrypto::hash::{Blake2b256, HashFunction};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use sui_sdk_types::Address;
+
+/// Resolved package recorded in the lockfile.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct LockedPackage {
+    pub package_id: Address,
+    pub version: u64,
+    pub digest: String,
+}
+
+impl LockedPackage {
+    pub fn new(package: &RawPackage) -> Result<Self, Error> {
+        Ok(Self {
+            package_id: package.package_id,
+            version: package.version,
+            digest: Self::digest(package)?,
+        })
+    }
+
+    /// Digest of the module bytes of the package.
+    pub fn digest(package: &RawPackage) -> Result<String, Error> {
+        let bytes = bcs::to_bytes(&package.modules).map_err(|e| Error::Lockfile(e.to_string()))?;
+        Ok(Hex::encode(Blake2b256::digest(&bytes).digest))
+    }
+}
+
+/// Lockfile pinning every package used for code generation, meant to be checked into source
+/// control so that builds fail if the on-chain content of a package changes.
+///
+/// Entries are keyed by network and by the package as written in `move_contract!`, suffixed
+/// with the requested version if any.
+pub struct Lockfile {
+    path: PathBuf,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct LockfileContent {
+    packages: BTreeMap<String, BTreeMap<String, LockedPackage>>,
+}
+
+impl Lockfile {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    fn read(&self) -> Result<LockfileContent, Error> {
+        if !self.path.exists() {
+            return Ok(LockfileContent::default());
+        }
+        let mut file = File::open(&self.path).map_err(|e| Error::io(&self.path, e))?;
+        file.lock_shared().map_err(|e| Error::io(&self.path, e))?;
+        self.read_content(&mut file)
+    }
+
+    fn read_content(&self, file: &mut File) -> Result<LockfileContent, Error> {
+        let mut content = String::new();
+        file.read_to_string(&mut content)
+            .map_err(|e| Error::io(&self.path, e))?;
+        // The lockfile is created empty by the first insertion.
+        if content.trim().is_empty() {
+            return Ok(LockfileContent::default());
+        }
+        serde_json::from_str(&content)
+            .map_err(|e| Error::Lockfile(format!("invalid lockfile {:?}: {e}", self.path)))
+    }
+
+    fn key(package: &str, version: Option<u64>) -> String {
+        match version {
+            Some(version) => format!("{package}/{version}"),
+            None => package.to_string(),
+        }
+    }
+
+    pub fn get(
+        &self,
+        network: &SuiNetwork,
+        package: &str,
+        version: Option<u64>,
+    ) -> Result<Option<LockedPackage>, Error> {
+        Ok(self
+            .read()?
+            .packages
+            .get(&network.name())
+            .and_then(|packages| packages.get(&Self::key(package, version)))
+            .cloned())
+    }
+
+    /// Locks `fetched`, or verifies it if the package was locked since [`Self::get`].
+    pub fn insert(
+        &self,
+        network: &SuiNetwork,
+        package: &str,
+        version: Option<u64>,
+        fetched: &RawPackage,
+    ) -> Result<(), Error> {
+        // Expansions of `move_contract!` run in parallel, the file stays locked from the read to
+        // the write so that concurrent insertions are neither lost nor overwritten.
+        let io_error = |e| Error::io(&self.path, e);
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&self.path)
+            .map_err(io_error)?;
+        file.lock().map_err(io_error)?;
+        let mut content = self.read_content(&mut file)?;
+        let packages = content.packages.entry(network.name()).or_default();
+        let key = Self::key(package, version);
+        if let Some(locked) = packages.get(&key) {
+            return self.verify(package, locked, fetched);
+        }
+        packages.insert(key, LockedPackage::new(fetched)?);
+        let content =
+            serde_json::to_string_pretty(&content).map_err(|e| Error::Lockfile(e.to_string()))?;
+        file.set_len(0).map_err(io_error)?;
+        file.seek(SeekFrom::Start(0)).map_err(io_error)?;
+        file.write_all((content + "\n").as_bytes())
+            .map_err(io_error)?;
+        Ok(())
+    }
+
+    /// Checks a fetched package against its lockfile entry.
+    pub fn verify(
+        &self,
+        package: &str,
+        locked: &LockedPackage,
+        fetched: &RawPackage,
+    ) -> Result<(), Error> {
+        let actual = LockedPackage::new(fetched)?;
+        if &actual != locked {
+            return Err(Error::Lockfile(format!(
+                "package [{package}] does not match {:?}: locked {}@{} ({}), found {}@{} ({}). \
+                 Remove the entry from the lockfile to accept the new content.",
+                self.path,
+                locked.package_id,
+                locked.version,
+                locked.digest,
+                actual.package_id,
+                actual.version,
+                actual.digest
+            )));
+        }
+        Ok(())
+    }
+}
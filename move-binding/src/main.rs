@@ -0,0 +1,107 @@
+use clap::Parser;
+use move_binding::error::Error;
+use move_binding::lockfile::Lockfile;
+use move_binding::move_codegen::{CodegenOptions, Dependency, MoveCodegen};
+use move_binding::package_cache::PackageCache;
+use move_binding::package_provider::{
+    Backend, LocalModuleProvider, ModuleProvider, MoveModuleProvider,
+};
+use move_binding::SuiNetwork;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+/// Generate Rust bindings for a Move package and write them to files.
+#[derive(Parser)]
+#[command(name = "move-binding", version)]
+struct Args {
+    /// Package id or MVR name. Defaults to the root package when reading a local build.
+    #[arg(long, required_unless_present = "path")]
+    package: Option<String>,
+    /// Name of the generated Rust module.
+    #[arg(long)]
+    alias: String,
+    /// Package version to generate bindings for, defaults to the latest version.
+    #[arg(long = "package-version")]
+    version: Option<u64>,
+    /// Rust path the generated package module is mounted under.
+    #[arg(long, default_value = "crate")]
+    base_path: String,
+    /// Dependency package bindings referenced by the generated types, as `path` for the
+    /// framework packages or `path=package` otherwise, e.g. `crate::sui` or `crate::wal=0x8270..`.
+    #[arg(long = "dep")]
+    deps: Vec<Dependency>,
+    /// Directory the bindings are written to.
+    #[arg(long, default_value = ".")]
+    out_dir: PathBuf,
+    /// Read the package from a `sui move build` output directory instead of the network.
+    #[arg(long)]
+    path: Option<PathBuf>,
+    #[arg(long, default_value = "mainnet")]
+    network: SuiNetwork,
+    #[arg(long)]
+    gql_url: Option<String>,
+    #[arg(long)]
+    mvr_url: Option<String>,
+    #[arg(long)]
+    rpc_url: Option<String>,
+    /// API packages are fetched from, `graphql` or `jsonrpc`.
+    #[arg(long, default_value = "graphql")]
+    backend: Backend,
+    /// Lockfile pinning the fetched packages.
+    #[arg(long)]
+    lockfile: Option<PathBuf>,
+    /// Also generate wrappers for `public(package)` functions.
+    #[arg(long)]
+    include_package_private: bool,
+    /// Generate the dependency packages missing from `--dep` in a nested `deps` module.
+    #[arg(long)]
+    include_deps: bool,
+}
+
+fn main() -> ExitCode {
+    match run(Args::parse()) {
+        Ok(files) => {
+            files.iter().for_each(|file| println!("{}", file.display()));
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: Args) -> Result<Vec<PathBuf>, Error> {
+    let network = args
+        .network
+        .with_endpoints(args.gql_url, args.mvr_url, args.rpc_url)
+        .with_env_overrides()?;
+
+    let module_provider: Box<dyn ModuleProvider> = match args.path {
+        Some(path) => Box::new(LocalModuleProvider::new(path)),
+        None => {
+            let mut provider = MoveModuleProvider::new(network)
+                .with_backend(args.backend)
+                .with_cache(PackageCache::from_env());
+            if let Some(lockfile) = args.lockfile {
+                provider = provider.with_lockfile(Lockfile::new(lockfile));
+            }
+            Box::new(provider)
+        }
+    };
+
+    let options = CodegenOptions {
+        include_package_private: args.include_package_private,
+        deps: args.deps,
+        include_deps: args.include_deps,
+    };
+    MoveCodegen::write_package(
+        module_provider.as_ref(),
+        &args.package.unwrap_or_default(),
+        args.version,
+        &args.alias,
+        &args.base_path,
+        &options,
+        &args.out_dir,
+    )
+}
@@ -1,100 +1,590 @@
-use crate::package_provider::{ModuleProvider, MoveModuleProvider};
-use crate::types::ToRustType;
+use crate::error::Error;
+use crate::package_provider::{
+    FunctionNames, ModuleProvider, MoveModuleProvider, Package, PackageDependency, SourceNames,
+};
+use crate::types::{collect_instantiations, collect_packages, ToRustType};
 use crate::SuiNetwork;
-use anyhow::anyhow;
 use itertools::Itertools;
-use move_binary_format::normalized::{Enum, Function, Struct, Type};
+use move_binary_format::file_format::{AbilitySet, Visibility};
+use move_binary_format::normalized::{Constant, Enum, Function, Module, Struct, Type};
 use move_core_types::account_address::AccountAddress;
 use move_core_types::identifier::Identifier;
-use once_cell::sync::Lazy;
 use proc_macro2::{Ident, TokenStream};
 use quote::quote;
-use std::collections::{BTreeMap, HashMap};
-use std::sync::RwLock;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
-pub static BINDING_REGISTRY: Lazy<RwLock<HashMap<AccountAddress, String>>> =
-    Lazy::new(|| RwLock::new(HashMap::new()));
+/// Rust paths of the packages' bindings, keyed by the addresses defining their types.
+pub type PackagePaths = HashMap<AccountAddress, String>;
+
+/// Abilities and phantom type parameters of datatypes, keyed by the original id of their package,
+/// their module and their name.
+type DatatypeAbilities = HashMap<(AccountAddress, String, String), (AbilitySet, Vec<bool>)>;
 
 pub struct MoveCodegen;
 
+#[derive(Default)]
+pub struct CodegenOptions {
+    /// Also generate wrappers for `public(package)` functions. They cannot be called from a
+    /// programmable transaction, but are useful for tooling inspecting the package.
+    pub include_package_private: bool,
+    /// Packages whose bindings are generated elsewhere, and referenced by the generated types.
+    pub deps: Vec<Dependency>,
+    /// Generate the dependency packages referenced by the package and missing from `deps`, in
+    /// a `deps` module nested in the package module.
+    pub include_deps: bool,
+}
+
+/// Location of the bindings of a dependency package, e.g. `crate::sui` or
+/// `other_crate::bridge = "0xb"`.
+#[derive(Clone, Debug)]
+pub struct Dependency {
+    /// Rust path of the package module.
+    pub path: String,
+    /// Package id or MVR name, inferred from the last segment of the path for the framework
+    /// packages when not set.
+    pub package: Option<String>,
+}
+
+impl Dependency {
+    /// Addresses the types of the dependency package are referred to by.
+    fn addresses(
+        &self,
+        module_provider: &dyn ModuleProvider,
+    ) -> Result<Vec<AccountAddress>, Error> {
+        if let Some(package) = &self.package {
+            let package = module_provider.get_package(package, None)?;
+            return Ok(package_addresses(&package).collect());
+        }
+        let name = self.path.rsplit("::").next().unwrap_or_default();
+        let address = match name {
+            "std" | "move_lib" | "move_stdlib" => AccountAddress::ONE,
+            "sui" | "sui_framework" => AccountAddress::TWO,
+            "sui_system" => AccountAddress::from_suffix(0x3),
+            "bridge" => AccountAddress::from_suffix(0xb),
+            _ => {
+                return Err(Error::InvalidDependency {
+                    path: self.path.clone(),
+                    reason: format!(
+                        "unknown package, declare it as `{} = \"<package>\"`",
+                        self.path
+                    ),
+                })
+            }
+        };
+        Ok(vec![address])
+    }
+}
+
+impl FromStr for Dependency {
+    type Err = Error;
+
+    /// Parses `path` or `path=package`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (path, package) = match s.split_once('=') {
+            Some((path, package)) => (path, Some(package.trim().to_string())),
+            None => (s, None),
+        };
+        let path = path.split_whitespace().collect::<String>();
+        if syn::parse_str::<syn::Path>(&path).is_err() {
+            return Err(Error::InvalidDependency {
+                path,
+                reason: "not a Rust path".to_string(),
+            });
+        }
+        Ok(Self { path, package })
+    }
+}
+
+struct GeneratedModule {
+    name: String,
+    tokens: TokenStream,
+    /// Name of the enum of the events declared by the module, if any.
+    event_enum: Option<Ident>,
+    /// Name of the enum of the objects declared by the module, if any.
+    object_enum: Option<Ident>,
+}
+
+struct GeneratedPackage {
+    version: u64,
+    modules: Vec<GeneratedModule>,
+    /// Dependency packages generated along with the package, see
+    /// [`CodegenOptions::include_deps`].
+    deps: Vec<(String, GeneratedPackage)>,
+}
+
+impl GeneratedPackage {
+    fn to_tokens(&self, package_alias: &str) -> TokenStream {
+        let module_tokens = self.modules.iter().map(|module| {
+            let module_ident = Ident::new(&module.name, proc_macro2::Span::call_site());
+            let tokens = &module.tokens;
+            quote! {
+                pub mod #module_ident{
+                    #tokens
+                }
+            }
+        });
+        let deps_tokens = if self.deps.is_empty() {
+            quote! {}
+        } else {
+            let deps = self.deps.iter().map(|(alias, dep)| dep.to_tokens(alias));
+            quote! {
+                pub mod deps{
+                    #(#deps)*
+                }
+            }
+        };
+        let package_ident = Ident::new(package_alias, proc_macro2::Span::call_site());
+        let version = self.version;
+        let event_tokens = self.event_tokens();
+        let object_tokens = self.object_tokens();
+
+        quote! {
+            pub mod #package_ident{
+                pub const PACKAGE_VERSION:u64 = #version;
+                #event_tokens
+                #object_tokens
+                #(#module_tokens)*
+                #deps_tokens
+            }
+        }
+    }
+
+    /// Names, package enum variants and paths of the module enums selected by `module_enum`.
+    fn module_enums(
+        &self,
+        module_enum: impl Fn(&GeneratedModule) -> Option<&Ident>,
+    ) -> (Vec<&String>, Vec<Ident>, Vec<TokenStream>) {
+        self.modules
+            .iter()
+            .filter_map(|module| {
+                let enum_ident = module_enum(module)?;
+                let module_ident = Ident::new(&module.name, proc_macro2::Span::call_site());
+                let variant =
+                    Ident::new(&to_camel_case(&module.name), proc_macro2::Span::call_site());
+                Some((&module.name, variant, quote! {#module_ident::#enum_ident}))
+            })
+            .multiunzip()
+    }
+
+    /// Enum of the events of all modules of the package, dispatching to the module event enums.
+    fn event_tokens(&self) -> TokenStream {
+        let (module_names, variants, enums) = self.module_enums(|m| m.event_enum.as_ref());
+        if variants.is_empty() {
+            return quote! {};
+        }
+        quote! {
+            /// Events emitted by the modules of this package.
+            #[derive(Debug)]
+            pub enum Event {
+                #(#variants(#enums)),*
+            }
+
+            impl Event {
+                /// Decode an event of this package from its type and BCS content, returns `None`
+                /// for events of other packages.
+                pub fn try_from_type_and_bcs(
+                    type_: &move_types::StructTag,
+                    bcs: &[u8],
+                ) -> Result<Option<Self>, move_types::bcs::Error> {
+                    match type_.module.as_str() {
+                        #(#module_names => Ok(#enums::try_from_type_and_bcs(type_, bcs)?.map(Self::#variants)),)*
+                        _ => Ok(None),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Enum of the objects of all modules of the package, and `decode_object` dispatching to the
+    /// module object enums.
+    fn object_tokens(&self) -> TokenStream {
+        let (module_names, variants, enums) = self.module_enums(|m| m.object_enum.as_ref());
+        if variants.is_empty() {
+            return quote! {};
+        }
+        quote! {
+            /// Objects of the types declared by the modules of this package.
+            #[derive(Debug)]
+            pub enum AnyObject {
+                #(#variants(#enums)),*
+            }
+
+            /// Decode an object of this package from its type and BCS content, returns `None` for
+            /// objects of other packages.
+            pub fn decode_object(
+                type_: &move_types::StructTag,
+                bcs: &[u8],
+            ) -> Result<Option<AnyObject>, move_types::bcs::Error> {
+                match type_.module.as_str() {
+                    #(#module_names => Ok(#enums::try_from_type_and_bcs(type_, bcs)?.map(AnyObject::#variants)),)*
+                    _ => Ok(None),
+                }
+            }
+        }
+    }
+
+    fn write(&self, package_dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), Error> {
+        fs::create_dir_all(package_dir).map_err(|e| Error::io(package_dir, e))?;
+
+        for module in &self.modules {
+            let path = package_dir.join(format!("{}.rs", module.name));
+            write_file(&path, &module.name, module.tokens.clone())?;
+            files.push(path);
+        }
+
+        let mut module_idents = self
+            .modules
+            .iter()
+            .map(|module| Ident::new(&module.name, proc_macro2::Span::call_site()))
+            .collect::<Vec<_>>();
+        if !self.deps.is_empty() {
+            let deps_dir = package_dir.join("deps");
+            for (alias, dep) in &self.deps {
+                dep.write(&deps_dir.join(alias), files)?;
+            }
+            let dep_idents = self
+                .deps
+                .iter()
+                .map(|(alias, _)| Ident::new(alias, proc_macro2::Span::call_site()));
+            let path = deps_dir.join("mod.rs");
+            write_file(&path, "deps", quote! { #(pub mod #dep_idents;)* })?;
+            files.push(path);
+            module_idents.push(Ident::new("deps", proc_macro2::Span::call_site()));
+        }
+
+        let version = self.version;
+        let event_tokens = self.event_tokens();
+        let object_tokens = self.object_tokens();
+        let path = package_dir.join("mod.rs");
+        write_file(
+            &path,
+            &package_dir.display().to_string(),
+            quote! {
+                pub const PACKAGE_VERSION:u64 = #version;
+                #event_tokens
+                #object_tokens
+                #(pub mod #module_idents;)*
+            },
+        )?;
+        files.push(path);
+        Ok(())
+    }
+}
+
 impl MoveCodegen {
+    /// Generates bindings for `package`, pinned to `version` if set.
     pub fn expand(
         network: SuiNetwork,
         package: &str,
+        version: Option<u64>,
         package_alias: &str,
         base_path: &str,
-    ) -> Result<TokenStream, anyhow::Error> {
+    ) -> Result<TokenStream, Error> {
         let module_provider = MoveModuleProvider::new(network);
-        let package = module_provider.get_package(package)?;
+        Self::expand_with_provider(
+            &module_provider,
+            package,
+            version,
+            package_alias,
+            base_path,
+            &CodegenOptions::default(),
+        )
+    }
 
-        // register package path
-        let mut cache = BINDING_REGISTRY
-            .write()
-            .map_err(|e| anyhow!("Failed to acquire write lock: {}", e))?;
-        package
-            .type_origin_table
-            .iter()
-            .flat_map(|(_, m)| m.values())
-            .dedup()
-            .for_each(|addr| {
-                cache.insert(addr.clone(), format!("{base_path}::{package_alias}"));
-            });
-        drop(cache);
+    pub fn expand_with_provider(
+        module_provider: &dyn ModuleProvider,
+        package: &str,
+        version: Option<u64>,
+        package_alias: &str,
+        base_path: &str,
+        options: &CodegenOptions,
+    ) -> Result<TokenStream, Error> {
+        let package = Self::generate(
+            module_provider,
+            package,
+            version,
+            package_alias,
+            base_path,
+            options,
+        )?;
+        Ok(package.to_tokens(package_alias))
+    }
 
-        let module_tokens = package
-            .module_map
-            .iter()
-            .map(|(module_name, module)| {
-                let module_ident = Ident::new(module_name, proc_macro2::Span::call_site());
-                let type_origin_table = package
-                    .type_origin_table
-                    .get(module_name)
-                    .cloned()
-                    .unwrap_or_default();
+    /// Generates bindings for `package` and writes them to `out_dir/{package_alias}`, with one
+    /// file per Move module and a `mod.rs` declaring them. `base_path` must be the Rust path the
+    /// generated `mod.rs` is mounted at, without the package alias.
+    ///
+    /// Returns the paths of the written files.
+    pub fn write_package(
+        module_provider: &dyn ModuleProvider,
+        package: &str,
+        version: Option<u64>,
+        package_alias: &str,
+        base_path: &str,
+        options: &CodegenOptions,
+        out_dir: &Path,
+    ) -> Result<Vec<PathBuf>, Error> {
+        let package = Self::generate(
+            module_provider,
+            package,
+            version,
+            package_alias,
+            base_path,
+            options,
+        )?;
+        let mut files = vec![];
+        package.write(&out_dir.join(package_alias), &mut files)?;
+        Ok(files)
+    }
 
-                let mut struct_fun_tokens =
-                    Self::create_structs(&module.structs, &type_origin_table)?;
-                struct_fun_tokens.extend(Self::create_enums(&module.enums, &type_origin_table));
-                struct_fun_tokens.extend(Self::create_funs(&module.functions));
+    fn generate(
+        module_provider: &dyn ModuleProvider,
+        package: &str,
+        version: Option<u64>,
+        package_alias: &str,
+        base_path: &str,
+        options: &CodegenOptions,
+    ) -> Result<GeneratedPackage, Error> {
+        let package = module_provider.get_package(package, version)?;
 
-                Ok::<_, anyhow::Error>(if struct_fun_tokens.is_empty() {
-                    quote! {}
-                } else {
-                    let addr_byte_ident = module.address.to_vec();
-                    quote! {
-                        pub mod #module_ident{
-                            use std::str::FromStr;
-                            use move_binding_derive::{MoveStruct, Key};
-                            use move_types::{MoveType, Address, Identifier, ObjectId};
-                            use move_types::functions::{Arg, Ref, MutRef};
-                            pub const PACKAGE_ID: Address = Address::new([#(#addr_byte_ident),*]);
-                            pub const MODULE_NAME: &str = #module_name;
-                            #(#struct_fun_tokens)*
-                        }
-                    }
-                })
+        let mut package_paths = PackagePaths::new();
+        for dep in &options.deps {
+            for address in dep.addresses(module_provider)? {
+                package_paths.insert(address, dep.path.clone());
+            }
+        }
+        let package_path = format!("{base_path}::{package_alias}");
+        for address in package_addresses(&package) {
+            package_paths.insert(address, package_path.clone());
+        }
+
+        // Dependencies referenced by the package and missing from `deps`, generated in the
+        // `deps` module of the package. The linkage of a package covers its transitive
+        // dependencies, so the dependencies of dependencies are looked up in it as well.
+        let mut deps = vec![];
+        if options.include_deps {
+            let linkage = package
+                .dependencies
+                .iter()
+                .map(|dep| (dep.original_id, dep))
+                .collect::<HashMap<_, _>>();
+            let mut pending = Self::referenced_packages(&package, options);
+            while let Some(address) = pending.pop_first() {
+                if package_paths.contains_key(&address) {
+                    continue;
+                }
+                // Unknown packages are reported when resolving the types referring to them.
+                let Some(dependency) = linkage.get(&address) else {
+                    continue;
+                };
+                let dep = module_provider.get_package(&dependency.package, None)?;
+                let alias = dependency_alias(dependency);
+                let dep_path = format!("{package_path}::deps::{alias}");
+                for address in package_addresses(&dep) {
+                    package_paths.insert(address, dep_path.clone());
+                }
+                pending.extend(Self::referenced_packages(&dep, options));
+                deps.push((alias, dep));
+            }
+        }
+
+        let mut abilities = DatatypeAbilities::new();
+        for package in deps.iter().map(|(_, dep)| dep).chain([&package]) {
+            Self::load_abilities(module_provider, package, options, &mut abilities)?;
+        }
+
+        let deps = deps
+            .into_iter()
+            .map(|(alias, dep)| {
+                let generated = GeneratedPackage {
+                    version: dep.version,
+                    modules: Self::generate_modules(&dep, &package_paths, &abilities, options)?,
+                    deps: vec![],
+                };
+                Ok((alias, generated))
             })
-            .collect::<Result<Vec<_>, _>>()?;
+            .collect::<Result<Vec<_>, Error>>()?;
 
-        let package_ident = Ident::new(&package_alias, proc_macro2::Span::call_site());
-        let version = package.version;
+        Ok(GeneratedPackage {
+            version: package.version,
+            modules: Self::generate_modules(&package, &package_paths, &abilities, options)?,
+            deps,
+        })
+    }
 
-        Ok(quote! {
-            pub mod #package_ident{
-                pub const PACKAGE_VERSION:u64 = #version;
-                #(#module_tokens)*
+    /// Adds the abilities of the datatypes of `package`, and of the datatypes returned by its
+    /// generated functions, to tell the results which must be used apart.
+    fn load_abilities(
+        module_provider: &dyn ModuleProvider,
+        package: &Package,
+        options: &CodegenOptions,
+        abilities: &mut DatatypeAbilities,
+    ) -> Result<(), Error> {
+        add_abilities(package, abilities);
+
+        let mut returned = BTreeSet::new();
+        package
+            .module_map
+            .values()
+            .flat_map(|module| module.functions.values())
+            .filter(|fun| is_generated(fun, options))
+            .flat_map(|fun| &fun.return_)
+            .for_each(|t| collect_packages(t, &mut returned));
+        for dependency in &package.dependencies {
+            let loaded = abilities
+                .keys()
+                .any(|(address, _, _)| *address == dependency.original_id);
+            if returned.contains(&dependency.original_id) && !loaded {
+                let dep = module_provider.get_package(&dependency.package, None)?;
+                add_abilities(&dep, abilities);
             }
-        })
+        }
+        Ok(())
+    }
+
+    /// Addresses of the packages defining the types used by the generated bindings of
+    /// `package`.
+    fn referenced_packages(
+        package: &Package,
+        options: &CodegenOptions,
+    ) -> BTreeSet<AccountAddress> {
+        let mut packages = BTreeSet::new();
+        for module in package.module_map.values() {
+            signature_types(module, options).for_each(|t| collect_packages(t, &mut packages));
+        }
+        packages
+    }
+
+    /// Generates the content of each module of the package, modules without any item are skipped.
+    fn generate_modules(
+        package: &Package,
+        package_paths: &PackagePaths,
+        abilities: &DatatypeAbilities,
+        options: &CodegenOptions,
+    ) -> Result<Vec<GeneratedModule>, Error> {
+        // Instantiations of the generic structs of the package found in its signatures, e.g.
+        // `Coin<SUI>` in `0x2`, which the object enums dispatch to.
+        let mut instantiations = vec![];
+        for module in package.module_map.values() {
+            signature_types(module, options)
+                .for_each(|t| collect_instantiations(t, &mut instantiations));
+        }
+
+        let mut modules = vec![];
+        for (module_name, module) in &package.module_map {
+            let type_origin_table = package
+                .type_origin_table
+                .get(module_name)
+                .cloned()
+                .unwrap_or_default();
+
+            let datatype_names = module
+                .structs
+                .keys()
+                .chain(module.enums.keys())
+                .map(|name| name.as_str())
+                .collect::<Vec<_>>();
+            let mut struct_fun_tokens = Self::create_structs(
+                &module.structs,
+                &type_origin_table,
+                package_paths,
+                &datatype_names,
+            )?;
+            struct_fun_tokens.extend(Self::create_enums(
+                &module.enums,
+                &type_origin_table,
+                package_paths,
+            )?);
+            let event_enum = Self::create_events(&module.structs, &datatype_names);
+            if let Some((_, tokens)) = &event_enum {
+                struct_fun_tokens.push(tokens.clone());
+            }
+            let module_instantiations = instantiations
+                .iter()
+                .copied()
+                .filter(|t| {
+                    matches!(t, Type::Struct { address, module: type_module, .. }
+                        if *address == module.address && type_module.as_str() == module_name)
+                })
+                .collect::<Vec<_>>();
+            let object_enum = Self::create_objects(
+                &module.structs,
+                &module_instantiations,
+                package_paths,
+                &datatype_names,
+            );
+            if let Some((_, tokens)) = &object_enum {
+                struct_fun_tokens.push(tokens.clone());
+            }
+            struct_fun_tokens.extend(Self::create_constants(
+                &module.constants,
+                package.source_names.get(module_name),
+                &datatype_names,
+            ));
+            // The native coin commands of `move_types::coin` are typed with the framework coin,
+            // and receiving args with the framework `Receiving`.
+            if module.address == AccountAddress::TWO && module_name == "coin" {
+                struct_fun_tokens.push(quote! {
+                    impl<T0: MoveType> move_types::coin::CoinType for Coin<T0> {}
+                    impl move_types::coin::GasCoin for Coin<super::sui::SUI> {}
+                });
+            }
+            if module.address == AccountAddress::TWO && module_name == "transfer" {
+                struct_fun_tokens.push(quote! {
+                    impl<T0: MoveType> move_types::functions::Receiving for Receiving<T0> {}
+                });
+            }
+            struct_fun_tokens.extend(Self::create_funs(
+                &module.functions,
+                package.source_names.get(module_name),
+                package_paths,
+                abilities,
+                options,
+            )?);
+
+            if struct_fun_tokens.is_empty() {
+                continue;
+            }
+            // Calls are made to the generated version of the package, the types keep the ids
+            // of the versions defining them.
+            let addr_byte_ident = package.package_id.to_vec();
+            modules.push(GeneratedModule {
+                name: module_name.clone(),
+                tokens: quote! {
+                    use std::str::FromStr;
+                    use move_binding_derive::{MoveStruct, Key};
+                    use move_types::{MoveType, Address, Identifier, ObjectId};
+                    use move_types::functions::{Arg, Ref, MutRef};
+                    pub const PACKAGE_ID: Address = Address::new([#(#addr_byte_ident),*]);
+                    pub const MODULE_NAME: &str = #module_name;
+                    #(#struct_fun_tokens)*
+                },
+                event_enum: event_enum.map(|(ident, _)| ident),
+                object_enum: object_enum.map(|(ident, _)| ident),
+            });
+        }
+        Ok(modules)
     }
 
     fn create_structs(
         structs: &BTreeMap<Identifier, Struct>,
         type_origin_ids: &HashMap<String, AccountAddress>,
-    ) -> Result<Vec<TokenStream>, anyhow::Error> {
+        package_paths: &PackagePaths,
+        datatype_names: &[&str],
+    ) -> Result<Vec<TokenStream>, Error> {
         structs
             .iter()
             .map(|(name, move_struct)| {
-                Self::create_struct(name.as_str(), move_struct, type_origin_ids)
+                Self::create_struct(
+                    name.as_str(),
+                    move_struct,
+                    type_origin_ids,
+                    package_paths,
+                    datatype_names,
+                )
             })
             .collect()
     }
@@ -103,7 +593,9 @@ impl MoveCodegen {
         struct_name: &str,
         move_struct: &Struct,
         type_origin_id: &HashMap<String, AccountAddress>,
-    ) -> Result<TokenStream, anyhow::Error> {
+        package_paths: &PackagePaths,
+        datatype_names: &[&str],
+    ) -> Result<TokenStream, Error> {
         let (type_parameters, phantoms) = move_struct.type_parameters.iter().enumerate().fold(
             (vec![], vec![]),
             |(mut type_parameters, mut phantoms), (i, v)| {
@@ -122,7 +614,7 @@ impl MoveCodegen {
         );
 
         let struct_ident = Ident::new(&struct_name.to_string(), proc_macro2::Span::call_site());
-        let field_tokens = move_struct
+        let fields = move_struct
             .fields
             .iter()
             .map(|field| {
@@ -130,10 +622,29 @@ impl MoveCodegen {
                     &escape_keyword(field.name.as_str()),
                     proc_macro2::Span::call_site(),
                 );
-                let field_type: syn::Type = syn::parse_str(&field.type_.to_rust_type())?;
-                Ok(quote! {pub #field_ident: #field_type,})
+                let field_type = parse_type(&field.type_.to_rust_type(package_paths)?)?;
+                Ok((field_ident, field_type))
             })
-            .collect::<Result<Vec<_>, anyhow::Error>>()?;
+            .collect::<Result<Vec<_>, Error>>()?;
+        let field_tokens = fields
+            .iter()
+            .map(|(field_ident, field_type)| quote! {pub #field_ident: #field_type,})
+            .collect::<Vec<_>>();
+        let phantom_idents = move_struct
+            .type_parameters
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.is_phantom)
+            .map(|(i, _)| Ident::new(&format!("phantom_data_{i}"), proc_macro2::Span::call_site()))
+            .collect::<Vec<_>>();
+        let constructors = struct_constructors(&fields, &phantom_idents);
+        let builder = Self::create_struct_builder(
+            struct_name,
+            &struct_ident,
+            &type_parameters,
+            &fields,
+            datatype_names,
+        );
 
         let mut derives = vec![
             quote! {serde::Deserialize},
@@ -147,6 +658,12 @@ impl MoveCodegen {
         }
 
         let addr_byte_ident = type_origin_id[struct_name].to_vec();
+        let phantom_params = move_struct
+            .type_parameters
+            .iter()
+            .map(|t| t.is_phantom)
+            .collect::<Vec<_>>();
+        let ability_impls = ability_impls(&struct_ident, &move_struct.abilities, &phantom_params);
         Ok(if type_parameters.is_empty() {
             quote! {
                 #[derive(#(#derives),*)]
@@ -155,7 +672,10 @@ impl MoveCodegen {
                 }
                 impl #struct_ident{
                     pub const TYPE_ORIGIN_ID: Address = Address::new([#(#addr_byte_ident),*]);
+                    #constructors
                 }
+                #builder
+                #ability_impls
             }
         } else {
             quote! {
@@ -166,18 +686,87 @@ impl MoveCodegen {
                 }
                 impl <#(#type_parameters),*> #struct_ident<#(#type_parameters),*>{
                     pub const TYPE_ORIGIN_ID: Address = Address::new([#(#addr_byte_ident),*]);
+                    #constructors
                 }
+                #builder
+                #ability_impls
             }
         })
     }
 
+    /// Builder of structs with more than [`MAX_FIELDS_WITHOUT_BUILDER`] fields, skipped if its
+    /// name is taken by another datatype of the module or a field is named `build`.
+    fn create_struct_builder(
+        struct_name: &str,
+        struct_ident: &Ident,
+        type_parameters: &[TokenStream],
+        fields: &[(Ident, syn::Type)],
+        datatype_names: &[&str],
+    ) -> TokenStream {
+        let builder_name = format!("{struct_name}Builder");
+        if fields.len() <= MAX_FIELDS_WITHOUT_BUILDER
+            || datatype_names.contains(&builder_name.as_str())
+            || fields.iter().any(|(ident, _)| ident == "build")
+        {
+            return quote! {};
+        }
+        let builder_ident = Ident::new(&builder_name, proc_macro2::Span::call_site());
+        let (field_idents, field_types): (Vec<_>, Vec<_>) = fields.iter().cloned().unzip();
+        let field_names = field_idents.iter().map(|ident| ident.to_string());
+        let (generics, type_params, type_params_init) = if type_parameters.is_empty() {
+            (quote! {}, quote! {}, quote! {})
+        } else {
+            (
+                quote! {<#(#type_parameters),*>},
+                quote! {_type_params: std::marker::PhantomData<(#(#type_parameters,)*)>,},
+                quote! {_type_params: std::marker::PhantomData,},
+            )
+        };
+        let doc = format!("Builder of [`{struct_name}`], see [`{struct_name}::builder`].");
+        quote! {
+            #[doc = #doc]
+            pub struct #builder_ident #generics {
+                #(#field_idents: Option<#field_types>,)*
+                #type_params
+            }
+            impl #generics #struct_ident #generics {
+                pub fn builder() -> #builder_ident #generics {
+                    #builder_ident {
+                        #(#field_idents: None,)*
+                        #type_params_init
+                    }
+                }
+            }
+            impl #generics #builder_ident #generics {
+                #(
+                    pub fn #field_idents(mut self, #field_idents: #field_types) -> Self {
+                        self.#field_idents = Some(#field_idents);
+                        self
+                    }
+                )*
+                /// Fails if any field was not set.
+                pub fn build(self) -> Result<#struct_ident #generics, move_types::error::BindingError> {
+                    Ok(#struct_ident::new(#(
+                        self.#field_idents.ok_or(move_types::error::BindingError::MissingField {
+                            type_: #struct_name,
+                            field: #field_names,
+                        })?
+                    ),*))
+                }
+            }
+        }
+    }
+
     fn create_enums(
         enums: &BTreeMap<Identifier, Enum>,
         type_origin_ids: &HashMap<String, AccountAddress>,
-    ) -> Vec<TokenStream> {
+        package_paths: &PackagePaths,
+    ) -> Result<Vec<TokenStream>, Error> {
         enums
             .iter()
-            .map(|(name, move_enum)| Self::create_enum(name.as_str(), move_enum, type_origin_ids))
+            .map(|(name, move_enum)| {
+                Self::create_enum(name.as_str(), move_enum, type_origin_ids, package_paths)
+            })
             .collect()
     }
 
@@ -185,45 +774,54 @@ impl MoveCodegen {
         enum_name: &str,
         move_enum: &Enum,
         type_origin_id: &HashMap<String, AccountAddress>,
-    ) -> TokenStream {
+        package_paths: &PackagePaths,
+    ) -> Result<TokenStream, Error> {
         let enum_ident = Ident::new(&enum_name.to_string(), proc_macro2::Span::call_site());
-        let variant_tokens = move_enum.variants.iter().map(|variant| {
-            let variant_ident = Ident::new(
-                &escape_keyword(variant.name.as_str()),
-                proc_macro2::Span::call_site(),
-            );
+        let variant_tokens = move_enum
+            .variants
+            .iter()
+            .map(|variant| {
+                let variant_ident = Ident::new(
+                    &escape_keyword(variant.name.as_str()),
+                    proc_macro2::Span::call_site(),
+                );
 
-            if variant.fields.is_empty() {
-                return quote! {#variant_ident,};
-            }
+                if variant.fields.is_empty() {
+                    return Ok(quote! {#variant_ident,});
+                }
 
-            if variant
-                .fields
-                .iter()
-                .enumerate()
-                .all(|(i, field)| field.name.to_string() == format!("pos{}", i))
-            {
-                let field_types = variant.fields.iter().map(|field| {
-                    let field_type: syn::Type =
-                        syn::parse_str(&field.type_.to_rust_type()).unwrap();
-                    quote! {#field_type,}
-                });
+                if variant
+                    .fields
+                    .iter()
+                    .enumerate()
+                    .all(|(i, field)| field.name.to_string() == format!("pos{}", i))
+                {
+                    let field_types = variant
+                        .fields
+                        .iter()
+                        .map(|field| parse_type(&field.type_.to_rust_type(package_paths)?))
+                        .collect::<Result<Vec<_>, _>>()?;
 
-                return quote! {
-                    #variant_ident(#(#field_types)*),
-                };
-            }
+                    return Ok(quote! {
+                        #variant_ident(#(#field_types,)*),
+                    });
+                }
 
-            let field_tokens = variant.fields.iter().map(|field| {
-                let field_ident = Ident::new(
-                    &escape_keyword(field.name.as_str()),
-                    proc_macro2::Span::call_site(),
-                );
-                let field_type: syn::Type = syn::parse_str(&field.type_.to_rust_type()).unwrap();
-                quote! {#field_ident: #field_type,}
-            });
-            quote! { #variant_ident {#(#field_tokens)*},}
-        });
+                let field_tokens = variant
+                    .fields
+                    .iter()
+                    .map(|field| {
+                        let field_ident = Ident::new(
+                            &escape_keyword(field.name.as_str()),
+                            proc_macro2::Span::call_site(),
+                        );
+                        let field_type = parse_type(&field.type_.to_rust_type(package_paths)?)?;
+                        Ok(quote! {#field_ident: #field_type,})
+                    })
+                    .collect::<Result<Vec<_>, Error>>()?;
+                Ok(quote! { #variant_ident {#(#field_tokens)*},})
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
 
         let derives = vec![
             quote! {serde::Deserialize},
@@ -233,8 +831,9 @@ impl MoveCodegen {
         ];
 
         let addr_byte_ident = type_origin_id[enum_name].to_vec();
+        let ability_impls = ability_impls(&enum_ident, &move_enum.abilities, &[]);
 
-        quote! {
+        Ok(quote! {
             #[derive(#(#derives),*)]
             pub enum #enum_ident{
                 #(#variant_tokens)*
@@ -243,52 +842,397 @@ impl MoveCodegen {
             impl #enum_ident{
                 pub const TYPE_ORIGIN_ID: Address = Address::new([#(#addr_byte_ident),*]);
             }
+            #ability_impls
+        })
+    }
+
+    /// Enum of the events declared by the module, its structs with `copy` and `drop`. Generic
+    /// structs are left out, their type arguments cannot be resolved at compile time.
+    fn create_events(
+        structs: &BTreeMap<Identifier, Struct>,
+        datatype_names: &[&str],
+    ) -> Option<(Ident, TokenStream)> {
+        let events = structs
+            .iter()
+            .filter(|(_, s)| {
+                s.abilities.has_copy() && s.abilities.has_drop() && s.type_parameters.is_empty()
+            })
+            .map(|(name, _)| {
+                (
+                    name.as_str(),
+                    Ident::new(name.as_str(), proc_macro2::Span::call_site()),
+                )
+            })
+            .collect::<Vec<_>>();
+        if events.is_empty() {
+            return None;
+        }
+        let enum_name = if datatype_names.contains(&"Event") {
+            "ModuleEvent"
+        } else {
+            "Event"
+        };
+        let enum_ident = Ident::new(enum_name, proc_macro2::Span::call_site());
+        let (names, idents): (Vec<_>, Vec<_>) = events.into_iter().unzip();
+        let tokens = quote! {
+            /// Events declared by this module.
+            #[derive(Debug)]
+            pub enum #enum_ident {
+                #(#idents(#idents)),*
+            }
+
+            impl #enum_ident {
+                /// Decode an event of this module from its type and BCS content, returns `None`
+                /// for events of other modules.
+                pub fn try_from_type_and_bcs(
+                    type_: &move_types::StructTag,
+                    bcs: &[u8],
+                ) -> Result<Option<Self>, move_types::bcs::Error> {
+                    if type_.module.as_str() != MODULE_NAME || !type_.type_params.is_empty() {
+                        return Ok(None);
+                    }
+                    Ok(Some(match type_.name.as_str() {
+                        #(#names if type_.address == #idents::TYPE_ORIGIN_ID => {
+                            Self::#idents(move_types::bcs::from_bytes(bcs)?)
+                        })*
+                        _ => return Ok(None),
+                    }))
+                }
+            }
+        };
+        Some((enum_ident, tokens))
+    }
+
+    /// Enum of the objects declared by the module, its structs with `key`. Objects of generic
+    /// types are decoded as the instantiations of `instantiations` matching their type, and
+    /// otherwise kept as BCS, to be decoded once their type arguments are known.
+    fn create_objects(
+        structs: &BTreeMap<Identifier, Struct>,
+        instantiations: &[&Type],
+        package_paths: &PackagePaths,
+        datatype_names: &[&str],
+    ) -> Option<(Ident, TokenStream)> {
+        let (variants, arms): (Vec<_>, Vec<_>) = structs
+            .iter()
+            .filter(|(_, s)| s.abilities.has_key())
+            .flat_map(|(name, s)| {
+                let name = name.as_str();
+                let ident = Ident::new(name, proc_macro2::Span::call_site());
+                let type_params = s.type_parameters.len();
+                if type_params == 0 {
+                    return vec![(
+                        quote! {#ident(#ident)},
+                        quote! {
+                            #name if type_.address == #ident::TYPE_ORIGIN_ID
+                                && type_.type_params.is_empty() =>
+                            {
+                                Self::#ident(move_types::bcs::from_bytes(bcs)?)
+                            }
+                        },
+                    )];
+                }
+                let mut objects = Self::instantiated_objects(
+                    s,
+                    name,
+                    instantiations,
+                    package_paths,
+                    datatype_names,
+                );
+                let units = vec![quote! {()}; type_params];
+                objects.push((
+                    quote! {#ident(move_types::GenericObject)},
+                    quote! {
+                        #name if type_.address == #ident::<#(#units),*>::TYPE_ORIGIN_ID
+                            && type_.type_params.len() == #type_params =>
+                        {
+                            Self::#ident(move_types::GenericObject {
+                                type_: type_.clone(),
+                                contents: bcs.to_vec(),
+                            })
+                        }
+                    },
+                ));
+                objects
+            })
+            .unzip();
+        if variants.is_empty() {
+            return None;
         }
+        let enum_name = if datatype_names.contains(&"Object") {
+            "ModuleObject"
+        } else {
+            "Object"
+        };
+        let enum_ident = Ident::new(enum_name, proc_macro2::Span::call_site());
+        let tokens = quote! {
+            /// Objects of the types declared by this module.
+            #[derive(Debug)]
+            pub enum #enum_ident {
+                #(#variants),*
+            }
+
+            impl #enum_ident {
+                /// Decode an object of this module from its type and BCS content, returns `None`
+                /// for objects of other modules.
+                pub fn try_from_type_and_bcs(
+                    type_: &move_types::StructTag,
+                    bcs: &[u8],
+                ) -> Result<Option<Self>, move_types::bcs::Error> {
+                    if type_.module.as_str() != MODULE_NAME {
+                        return Ok(None);
+                    }
+                    Ok(Some(match type_.name.as_str() {
+                        #(#arms)*
+                        _ => return Ok(None),
+                    }))
+                }
+            }
+        };
+        Some((enum_ident, tokens))
     }
 
-    fn create_funs(funs: &BTreeMap<Identifier, Function>) -> Vec<TokenStream> {
+    /// Variants and match arms of the object enum for the instantiations of the generic struct
+    /// `name`, named after their type arguments, e.g. `CoinSUI` for `Coin<SUI>`. Instantiations
+    /// whose type arguments have no bindings are left to the `GenericObject` variant.
+    fn instantiated_objects(
+        struct_: &Struct,
+        name: &str,
+        instantiations: &[&Type],
+        package_paths: &PackagePaths,
+        datatype_names: &[&str],
+    ) -> Vec<(TokenStream, TokenStream)> {
+        let mut variant_names = vec![];
+        instantiations
+            .iter()
+            .filter(|t| {
+                matches!(t, Type::Struct { name: struct_name, type_arguments, .. }
+                    if struct_name.as_str() == name
+                        && type_arguments.len() == struct_.type_parameters.len())
+            })
+            .filter_map(|t| {
+                let variant = instantiation_name(t);
+                if datatype_names.contains(&variant.as_str()) || variant_names.contains(&variant) {
+                    return None;
+                }
+                let rust_type = parse_type(&t.to_rust_type(package_paths).ok()?).ok()?;
+                let variant_ident = Ident::new(&variant, proc_macro2::Span::call_site());
+                variant_names.push(variant);
+                Some((
+                    quote! {#variant_ident(#rust_type)},
+                    quote! {
+                        #name if *type_ == <#rust_type as move_types::MoveStruct>::struct_type() =>
+                        {
+                            Self::#variant_ident(move_types::bcs::from_bytes(bcs)?)
+                        }
+                    },
+                ))
+            })
+            .collect()
+    }
+
+    fn create_funs(
+        funs: &BTreeMap<Identifier, Function>,
+        source_names: Option<&SourceNames>,
+        package_paths: &PackagePaths,
+        abilities: &DatatypeAbilities,
+        options: &CodegenOptions,
+    ) -> Result<Vec<TokenStream>, Error> {
         funs.iter()
-            .flat_map(|(name, fun)| Self::create_fun(name.as_str(), fun))
+            .map(|(name, fun)| {
+                let names = source_names.and_then(|names| names.functions.get(name.as_str()));
+                Self::create_fun(name.as_str(), fun, names, package_paths, abilities, options)
+            })
+            .flatten_ok()
             .collect()
     }
 
-    fn create_fun(fun_name: &str, fun: &Function) -> Option<TokenStream> {
-        let (param_names, mut params, need_lifetime) = fun.parameters
-            .iter()
-            .enumerate()
-            .fold((vec![], vec![], false), |(mut param_names, mut params, mut lifetime), (i, move_type)| {
-                let field_ident = Ident::new(&format!("p{i}"), proc_macro2::Span::call_site());
-                lifetime = lifetime || move_type.is_ref();
-                match &move_type {
-                    Type::Reference(r) |
-                    Type::MutableReference(r) => {
-                        // filter out TxContext
-                        if matches!(&**r, Type::Struct{address, name, ..} if address == &AccountAddress::TWO && name.as_str() == "TxContext") {
-                            return (param_names, params, lifetime);
+    /// Constants are only emitted when their names are known: from a source map, or for on-chain
+    /// bytecode, which keeps the constant pool but not the constant names, from the clever errors
+    /// naming their constant.
+    pub fn create_constants(
+        constants: &[Constant],
+        source_names: Option<&SourceNames>,
+        datatype_names: &[&str],
+    ) -> Vec<TokenStream> {
+        let Some(source_names) = source_names else {
+            return vec![];
+        };
+
+        let mut tokens = vec![];
+        let mut abort_codes = vec![];
+        let mut clever_codes = vec![];
+        let mut variants = vec![];
+        for (index, name) in &source_names.constants {
+            let Some(constant) = constants.get(*index as usize) else {
+                continue;
+            };
+            if syn::parse_str::<Ident>(name).is_err()
+                || matches!(
+                    name.as_str(),
+                    "PACKAGE_ID" | "PACKAGE_VERSION" | "MODULE_NAME"
+                )
+            {
+                continue;
+            }
+            let ident = Ident::new(name, proc_macro2::Span::call_site());
+            if let Some((rust_type, value)) = constant_value(&constant.type_, &constant.data) {
+                tokens.push(quote! {
+                    #[allow(non_upper_case_globals)]
+                    pub const #ident: #rust_type = #value;
+                });
+            }
+
+            if !name.starts_with('E') {
+                continue;
+            }
+            // Clever errors abort with the pool index of the error constant, see `AbortCode`.
+            clever_codes.push(quote! { #index => Some(Self::#ident), });
+            if constant.type_ == Type::U64 {
+                if let Ok(code) = bcs::from_bytes::<u64>(&constant.data) {
+                    abort_codes.push(quote! { #code => Some(Self::#ident), });
+                }
+            }
+            variants.push(ident);
+        }
+
+        if !variants.is_empty() {
+            let enum_name = if datatype_names.contains(&"Error") {
+                "AbortError"
+            } else {
+                "Error"
+            };
+            let enum_ident = Ident::new(enum_name, proc_macro2::Span::call_site());
+            tokens.push(quote! {
+                /// Abort codes declared by this module.
+                #[allow(non_camel_case_types)]
+                #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+                pub enum #enum_ident {
+                    #(#variants),*
+                }
+
+                impl #enum_ident {
+                    /// Resolve the abort code of a `MoveAbort` raised by this module.
+                    #[allow(unreachable_patterns)]
+                    pub fn from_abort_code(code: u64) -> Option<Self> {
+                        match move_types::abort::AbortCode::from(code) {
+                            move_types::abort::AbortCode::Clever { constant_index, .. } => {
+                                match constant_index {
+                                    #(#clever_codes)*
+                                    _ => None,
+                                }
+                            }
+                            move_types::abort::AbortCode::Code(code) => match code {
+                                #(#abort_codes)*
+                                _ => None,
+                            },
                         }
                     }
-                    _ => {}
                 }
-                param_names.push(quote! {#field_ident});
-                let field_type: syn::Type = syn::parse_str(&move_type.to_arg_type()).unwrap();
-                params.push(quote! {#field_ident: #field_type});
-                (param_names, params, lifetime)
             });
+        }
+        tokens
+    }
+
+    fn create_fun(
+        fun_name: &str,
+        fun: &Function,
+        names: Option<&FunctionNames>,
+        package_paths: &PackagePaths,
+        abilities: &DatatypeAbilities,
+        options: &CodegenOptions,
+    ) -> Result<Option<TokenStream>, Error> {
+        if !is_generated(fun, options) {
+            return Ok(None);
+        }
+        let doc = match fun.visibility {
+            Visibility::Public => quote! {},
+            _ if fun.is_entry => quote! {
+                /// Entry function: its arguments cannot be results of non-entry calls made
+                /// earlier in the transaction, so it only takes inputs, and its results cannot be
+                /// used by later calls.
+            },
+            _ => quote! {
+                /// Package-private function, only callable from modules of its own package.
+            },
+        };
+        let entry_only = fun.is_entry && fun.visibility != Visibility::Public;
+
+        // Use the source names of the parameters when known, `T{i}` and `p{i}` otherwise.
+        let type_param_names = (0..fun.type_parameters.len())
+            .map(|i| {
+                names
+                    .and_then(|names| names.type_parameters.get(i))
+                    .filter(|name| {
+                        !RESERVED_TYPE_NAMES.contains(&name.as_str())
+                            && syn::parse_str::<Ident>(name).is_ok()
+                    })
+                    .cloned()
+                    .unwrap_or_else(|| format!("T{i}"))
+            })
+            .collect::<Vec<_>>();
+
+        let (param_names, mut params, need_lifetime) = fun.parameters.iter().enumerate().try_fold(
+            (vec![], vec![], false),
+            |(mut param_names, mut params, mut lifetime), (i, move_type)| {
+                let param_name = names
+                    .and_then(|names| names.parameters.get(i))
+                    .map(|name| match name.as_str() {
+                        "builder" => "builder_".to_string(),
+                        _ => escape_keyword(name),
+                    })
+                    .filter(|name| syn::parse_str::<Ident>(name).is_ok())
+                    .unwrap_or_else(|| format!("p{i}"));
+                let field_ident = Ident::new(&param_name, proc_macro2::Span::call_site());
+                lifetime = lifetime || (move_type.is_ref() && !entry_only);
+                // filter out TxContext
+                if is_tx_context(move_type) {
+                    return Ok((param_names, params, lifetime));
+                }
+                param_names.push((field_ident.clone(), move_type.is_ref()));
+                // Entry-only functions only take inputs, so that the results of earlier commands
+                // cannot be passed to them.
+                let field_type = if entry_only {
+                    let value_type = match move_type {
+                        Type::Reference(t) | Type::MutableReference(t) => t.as_ref(),
+                        t => t,
+                    };
+                    parse_type(&format!(
+                        "move_types::functions::EntryArg<{}>",
+                        value_type.to_rust_type_with(package_paths, &type_param_names)?
+                    ))?
+                } else {
+                    parse_type(&move_type.to_arg_type_with(package_paths, &type_param_names)?)?
+                };
+                params.push(quote! {#field_ident: #field_type});
+                Ok::<_, Error>((param_names, params, lifetime))
+            },
+        )?;
         params.insert(
             0,
-            quote! {builder: &mut sui_transaction_builder::TransactionBuilder},
+            quote! {builder: &mut impl move_types::builder::PtbBuilder},
         );
+        // Arguments passed by value are consumed by the call, see `PtbBuilder::consume`.
+        let resolve_args = param_names.iter().map(|(name, is_ref)| {
+            if *is_ref || entry_only {
+                quote! {let #name = #name.resolve_arg(builder.builder());}
+            } else {
+                quote! {let #name = #name.consume(builder);}
+            }
+        });
+        let param_names = param_names.iter().map(|(name, _)| name);
 
         let returns = fun
             .return_
             .iter()
-            .flat_map(|move_type| syn::parse_str::<syn::Type>(&move_type.to_arg_type()).ok())
-            .collect::<Vec<_>>();
+            .map(|move_type| {
+                parse_type(&move_type.to_arg_type_with(package_paths, &type_param_names)?)
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
 
         let (types, mut types_with_ability) = fun.type_parameters.iter().enumerate().fold(
             (vec![], vec![]),
             |(mut types, mut types_with_ability), (i, v)| {
-                let ident = Ident::new(&format!("T{i}"), proc_macro2::Span::call_site());
+                let ident = Ident::new(&type_param_names[i], proc_macro2::Span::call_site());
                 types.push(ident.clone());
                 let mut abilities = vec![];
                 if v.has_key() {
@@ -296,6 +1240,7 @@ impl MoveCodegen {
                 } else {
                     abilities.push(quote! {MoveType});
                 }
+                abilities.extend(ability_markers(v));
                 types_with_ability.push(quote! {#ident: #(#abilities)+*});
                 (types, types_with_ability)
             },
@@ -307,10 +1252,63 @@ impl MoveCodegen {
 
         let fun_ident = Ident::new(fun_name, proc_macro2::Span::call_site());
 
-        let (maybe_returns, maybe_into) = if let Some(t) = returns.first() {
-            (quote! { -> #t}, quote! {.into()})
+        // Results without `drop` must be used by a later command of the transaction, they are
+        // reported to the builder to be checked. Results of generic types are only known to
+        // lack `drop` once instantiated, and are left out.
+        let must_use = fun
+            .return_
+            .iter()
+            .map(|t| !has_drop(t, abilities))
+            .collect::<Vec<_>>();
+        let maybe_must_use = if must_use.contains(&true) && !entry_only {
+            quote! {#[must_use = "results without the `drop` ability must be used by a later command"]}
         } else {
-            (quote! {}, quote! {;})
+            quote! {}
+        };
+
+        // Functions returning several values are mapped to a tuple of `NestedResult`s.
+        let (maybe_let, maybe_returns, maybe_result) = match returns.as_slice() {
+            _ if entry_only => (quote! {}, quote! {}, quote! {;}),
+            [] => (quote! {}, quote! {}, quote! {;}),
+            [t] => {
+                let track = must_use[0].then(|| quote! {builder.track_result(&result);});
+                (
+                    quote! {let result =},
+                    quote! { -> #t},
+                    quote! {;
+                        #track
+                        result.into()
+                    },
+                )
+            }
+            ts => {
+                let result_idents = (0..ts.len())
+                    .map(|i| Ident::new(&format!("result_{i}"), proc_macro2::Span::call_site()))
+                    .collect::<Vec<_>>();
+                let indices = 0..ts.len() as u16;
+                let tracks = must_use
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, m)| **m)
+                    .map(|(i, _)| {
+                        quote! {builder.track_result(&results[#i]);}
+                    });
+                (
+                    quote! {let result =},
+                    quote! { -> (#(#ts),*)},
+                    quote! {;
+                        let sui_sdk_types::Argument::Result(command) = result else {
+                            unreachable!("move calls return Argument::Result")
+                        };
+                        let results = [#(
+                            sui_sdk_types::Argument::NestedResult(command, #indices)
+                        ),*];
+                        #(#tracks)*
+                        let [#(#result_idents),*] = results;
+                        (#(#result_idents.into()),*)
+                    },
+                )
+            }
         };
 
         let sig = if types_with_ability.is_empty() {
@@ -324,27 +1322,357 @@ impl MoveCodegen {
         };
 
         let fun_impl = quote! {
+            #doc
+            #maybe_must_use
             #sig {
-                #(let #param_names = #param_names.resolve_arg(builder);)*
-                builder.move_call(
+                #(#resolve_args)*
+                #maybe_let builder.builder().move_call(
                     sui_transaction_builder::Function::new(
                         PACKAGE_ID,
                         Identifier::from_str(MODULE_NAME).unwrap(),
                         Identifier::from_str(#fun_name).unwrap(),
                         vec![#(#types::type_()),*],
                     ),
-                    vec![#(#param_names.into()),*],
+                    vec![#(#param_names),*],
                 )
-                #maybe_into
+                #maybe_result
             }
         };
-        Some(fun_impl)
+        Ok(Some(fun_impl))
+    }
+}
+
+/// Render a constant pool entry as a Rust type and value, for the types representable in a `const`.
+fn constant_value(type_: &Type, data: &[u8]) -> Option<(TokenStream, TokenStream)> {
+    macro_rules! primitive {
+        ($t:ident) => {{
+            let value: $t = bcs::from_bytes(data).ok()?;
+            (quote! { $t }, quote! { #value })
+        }};
+    }
+    macro_rules! vector {
+        ($t:ident) => {{
+            let values: Vec<$t> = bcs::from_bytes(data).ok()?;
+            (quote! { &[$t] }, quote! { &[#(#values),*] })
+        }};
+    }
+    Some(match type_ {
+        Type::Bool => primitive!(bool),
+        Type::U8 => primitive!(u8),
+        Type::U16 => primitive!(u16),
+        Type::U32 => primitive!(u32),
+        Type::U64 => primitive!(u64),
+        Type::U128 => primitive!(u128),
+        Type::Address => {
+            let value: AccountAddress = bcs::from_bytes(data).ok()?;
+            let bytes = value.to_vec();
+            (quote! { Address }, quote! { Address::new([#(#bytes),*]) })
+        }
+        Type::Vector(t) => match t.as_ref() {
+            Type::Bool => vector!(bool),
+            Type::U8 => vector!(u8),
+            Type::U16 => vector!(u16),
+            Type::U32 => vector!(u32),
+            Type::U64 => vector!(u64),
+            Type::U128 => vector!(u128),
+            _ => return None,
+        },
+        _ => return None,
+    })
+}
+
+/// Marker traits of `move_types::abilities` for the `copy`, `drop` and `store` abilities of `abilities`.
+fn ability_markers(abilities: &AbilitySet) -> Vec<TokenStream> {
+    [
+        (abilities.has_copy(), quote! {move_types::abilities::Copy}),
+        (abilities.has_drop(), quote! {move_types::abilities::Drop}),
+        (abilities.has_store(), quote! {move_types::abilities::Store}),
+    ]
+    .into_iter()
+    .filter_map(|(has, marker)| has.then_some(marker))
+    .collect()
+}
+
+/// Structs with more fields also get a builder besides their constructor.
+const MAX_FIELDS_WITHOUT_BUILDER: usize = 4;
+
+/// Methods of generated structs that getters must not shadow: `type_` and `struct_type` are
+/// called on the struct type by the `MoveType` and `MoveStruct` impls.
+const RESERVED_METHODS: [&str; 4] = ["new", "builder", "type_", "struct_type"];
+
+/// `new` constructor filling the phantom markers, and a getter per field.
+fn struct_constructors(fields: &[(Ident, syn::Type)], phantom_idents: &[Ident]) -> TokenStream {
+    let (field_idents, field_types): (Vec<_>, Vec<_>) = fields.iter().cloned().unzip();
+    let getters = fields
+        .iter()
+        .filter(|(ident, _)| !RESERVED_METHODS.contains(&ident.to_string().as_str()))
+        .map(|(ident, type_)| {
+            quote! {
+                pub fn #ident(&self) -> &#type_ {
+                    &self.#ident
+                }
+            }
+        });
+    quote! {
+        #[allow(clippy::too_many_arguments)]
+        pub fn new(#(#field_idents: #field_types),*) -> Self {
+            Self {
+                #(#field_idents,)*
+                #(#phantom_idents: std::marker::PhantomData,)*
+            }
+        }
+        #(#getters)*
     }
 }
 
+/// Implements the ability marker traits for a datatype. Like in Move, an instantiation of a
+/// generic datatype has an ability only if its non-phantom type arguments have it as well.
+fn ability_impls(ident: &Ident, abilities: &AbilitySet, phantom_params: &[bool]) -> TokenStream {
+    let params = (0..phantom_params.len())
+        .map(|i| Ident::new(&format!("T{i}"), proc_macro2::Span::call_site()))
+        .collect::<Vec<_>>();
+    let impls = ability_markers(abilities).into_iter().map(|marker| {
+        if params.is_empty() {
+            return quote! { impl #marker for #ident {} };
+        }
+        let bounds = params
+            .iter()
+            .zip(phantom_params)
+            .map(|(param, is_phantom)| {
+                if *is_phantom {
+                    quote! {#param: MoveType}
+                } else {
+                    quote! {#param: MoveType + #marker}
+                }
+            });
+        quote! { impl<#(#bounds),*> #marker for #ident<#(#params),*> {} }
+    });
+    quote! { #(#impls)* }
+}
+
+/// Adds the abilities of the datatypes declared by `package`.
+fn add_abilities(package: &Package, abilities: &mut DatatypeAbilities) {
+    for (module_name, module) in &package.module_map {
+        let structs = module.structs.iter().map(|(name, s)| {
+            let phantoms = s.type_parameters.iter().map(|t| t.is_phantom).collect();
+            (name, (s.abilities, phantoms))
+        });
+        let enums = module.enums.iter().map(|(name, e)| {
+            let phantoms = e.type_parameters.iter().map(|t| t.is_phantom).collect();
+            (name, (e.abilities, phantoms))
+        });
+        for (name, datatype) in structs.chain(enums) {
+            abilities.insert(
+                (module.address, module_name.clone(), name.to_string()),
+                datatype,
+            );
+        }
+    }
+}
+
+/// Whether values of `type_` may have `drop`. Type parameters, and types of packages whose
+/// abilities were not loaded, are assumed to.
+fn has_drop(type_: &Type, abilities: &DatatypeAbilities) -> bool {
+    match type_ {
+        Type::Struct {
+            address,
+            module,
+            name,
+            type_arguments,
+        } => {
+            if (*address, module.as_str(), name.as_str()) == (AccountAddress::TWO, "object", "UID")
+            {
+                return false;
+            }
+            let key = (*address, module.to_string(), name.to_string());
+            match abilities.get(&key) {
+                Some((datatype_abilities, phantoms)) => {
+                    datatype_abilities.has_drop()
+                        && type_arguments
+                            .iter()
+                            .zip(phantoms)
+                            .all(|(t, is_phantom)| *is_phantom || has_drop(t, abilities))
+                }
+                None => type_arguments.iter().all(|t| has_drop(t, abilities)),
+            }
+        }
+        Type::Vector(t) => has_drop(t, abilities),
+        _ => true,
+    }
+}
+
+/// Only public and entry functions can be called from a programmable transaction.
+fn is_generated(fun: &Function, options: &CodegenOptions) -> bool {
+    match fun.visibility {
+        Visibility::Public => true,
+        _ if fun.is_entry => true,
+        Visibility::Friend => options.include_package_private,
+        _ => false,
+    }
+}
+
+/// Types the bindings of `module` refer to: the types of its fields and of the signatures of its
+/// generated functions.
+fn signature_types<'a>(
+    module: &'a Module,
+    options: &'a CodegenOptions,
+) -> impl Iterator<Item = &'a Type> + 'a {
+    let struct_fields = module.structs.values().flat_map(|s| &s.fields);
+    let variant_fields = module
+        .enums
+        .values()
+        .flat_map(|e| &e.variants)
+        .flat_map(|v| &v.fields);
+    let signatures = module
+        .functions
+        .values()
+        .filter(move |fun| is_generated(fun, options))
+        .flat_map(|fun| {
+            fun.parameters
+                .iter()
+                .filter(|t| !is_tx_context(t))
+                .chain(&fun.return_)
+        });
+    struct_fields
+        .chain(variant_fields)
+        .map(|field| &field.type_)
+        .chain(signatures)
+}
+
+/// Name of a type in the variant names of the object enums, e.g. `CoinSUI` for `Coin<SUI>`.
+fn instantiation_name(type_: &Type) -> String {
+    match type_ {
+        Type::Struct {
+            name,
+            type_arguments,
+            ..
+        } => std::iter::once(name.to_string())
+            .chain(type_arguments.iter().map(instantiation_name))
+            .collect(),
+        Type::Vector(t) => format!("Vec{}", instantiation_name(t)),
+        Type::Reference(t) | Type::MutableReference(t) => instantiation_name(t),
+        Type::Bool => "Bool".to_string(),
+        Type::U8 => "U8".to_string(),
+        Type::U16 => "U16".to_string(),
+        Type::U32 => "U32".to_string(),
+        Type::U64 => "U64".to_string(),
+        Type::U128 => "U128".to_string(),
+        Type::U256 => "U256".to_string(),
+        Type::Address => "Address".to_string(),
+        Type::Signer => "Signer".to_string(),
+        Type::TypeParameter(index) => format!("T{index}"),
+    }
+}
+
+pub(crate) fn is_tx_context(move_type: &Type) -> bool {
+    match move_type {
+        Type::Reference(t) | Type::MutableReference(t) => matches!(
+            &**t,
+            Type::Struct { address, module, name, .. }
+                if address == &AccountAddress::TWO
+                    && module.as_str() == "tx_context"
+                    && name.as_str() == "TxContext"
+        ),
+        _ => false,
+    }
+}
+
+/// Addresses the types of `package` are referred to by: the ids of the packages defining them,
+/// and the original id of the package used in bytecode.
+fn package_addresses(package: &Package) -> impl Iterator<Item = AccountAddress> + '_ {
+    package
+        .type_origin_table
+        .values()
+        .flat_map(|m| m.values().copied())
+        .chain(package.module_map.values().map(|m| m.address))
+        .unique()
+}
+
+/// Name of the module of a dependency generated with [`CodegenOptions::include_deps`].
+fn dependency_alias(dependency: &PackageDependency) -> String {
+    if let Some(name) = &dependency.name {
+        return to_snake_case(name);
+    }
+    match dependency.original_id {
+        AccountAddress::ONE => "std".to_string(),
+        AccountAddress::TWO => "sui".to_string(),
+        address if address == AccountAddress::from_suffix(0x3) => "sui_system".to_string(),
+        address if address == AccountAddress::from_suffix(0xb) => "bridge".to_string(),
+        address => format!("package_{}", address.short_str_lossless()),
+    }
+}
+
+/// Name of the package event variant of a module, e.g. `tx_context` to `TxContext`.
+fn to_camel_case(name: &str) -> String {
+    name.split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|c| c.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+fn to_snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 && !snake.ends_with('_') {
+                snake.push('_');
+            }
+            snake.push(c.to_ascii_lowercase());
+        } else if c.is_ascii_alphanumeric() {
+            snake.push(c);
+        } else if !snake.ends_with('_') {
+            snake.push('_');
+        }
+    }
+    escape_keyword(&snake)
+}
+
+const GENERATED_HEADER: &str = "// @generated by move-binding, do not edit.\n\n";
+
+fn write_file(path: &Path, name: &str, tokens: TokenStream) -> Result<(), Error> {
+    let file = syn::parse2::<syn::File>(tokens).map_err(|e| Error::Codegen {
+        name: name.to_string(),
+        reason: e.to_string(),
+    })?;
+    let content = format!("{GENERATED_HEADER}{}", prettyplease::unparse(&file));
+    fs::write(path, content).map_err(|e| Error::io(path, e))
+}
+
+fn parse_type(rust_type: &str) -> Result<syn::Type, Error> {
+    syn::parse_str(rust_type).map_err(|e| Error::UnsupportedType {
+        type_: rust_type.to_string(),
+        reason: e.to_string(),
+    })
+}
+
+/// Names used by the generated code, which type parameters must not shadow.
+const RESERVED_TYPE_NAMES: &[&str] = &[
+    "Address",
+    "Arg",
+    "Identifier",
+    "Key",
+    "MoveStruct",
+    "MoveType",
+    "MutRef",
+    "ObjectId",
+    "Option",
+    "Ref",
+    "String",
+    "Vec",
+];
+
 fn escape_keyword(name: &str) -> String {
     match name {
-        "for" | "ref" => {
+        "as" | "async" | "await" | "box" | "break" | "const" | "continue" | "crate" | "dyn"
+        | "else" | "enum" | "extern" | "false" | "fn" | "for" | "gen" | "if" | "impl" | "in"
+        | "let" | "loop" | "match" | "mod" | "move" | "mut" | "pub" | "ref" | "return" | "self"
+        | "static" | "struct" | "super" | "trait" | "true" | "try" | "type" | "unsafe" | "use"
+        | "where" | "while" | "yield" => {
             format!("{name}_")
         }
         _ => name.to_string(),
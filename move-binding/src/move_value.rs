@@ -0,0 +1,324 @@
+use crate::package_provider::Package;
+use move_binary_format::normalized::{Field, Module, Type};
+use move_core_types::account_address::AccountAddress;
+use move_core_types::u256::U256;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::str::FromStr;
+use sui_sdk_types::{Address, Identifier, ObjectId, StructTag, TypeTag};
+
+/// Move value of any type, decoded from BCS with [`MoveValueDecoder`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum MoveValue {
+    Bool(bool),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    U256(U256),
+    Address(Address),
+    /// `0x2::object::UID` or `0x2::object::ID`.
+    UID(ObjectId),
+    /// `0x1::string::String` or `0x1::ascii::String`.
+    String(String),
+    /// `0x1::option::Option`.
+    Option(Option<Box<MoveValue>>),
+    Vector(Vec<MoveValue>),
+    Struct {
+        type_: StructTag,
+        fields: Vec<(String, MoveValue)>,
+    },
+    Enum {
+        type_: StructTag,
+        variant: String,
+        fields: Vec<(String, MoveValue)>,
+    },
+}
+
+impl MoveValue {
+    /// Renders the value as JSON. Integers wider than 32 bits are rendered as strings, as they
+    /// may not fit in a JSON number, and enum variants as objects with a `@variant` member.
+    pub fn to_json(&self) -> Value {
+        match self {
+            MoveValue::Bool(v) => json!(v),
+            MoveValue::U8(v) => json!(v),
+            MoveValue::U16(v) => json!(v),
+            MoveValue::U32(v) => json!(v),
+            MoveValue::U64(v) => json!(v.to_string()),
+            MoveValue::U128(v) => json!(v.to_string()),
+            MoveValue::U256(v) => json!(v.to_string()),
+            MoveValue::Address(v) => json!(v.to_string()),
+            MoveValue::UID(v) => json!(v.to_string()),
+            MoveValue::String(v) => json!(v),
+            MoveValue::Option(v) => v.as_ref().map_or(Value::Null, |v| v.to_json()),
+            MoveValue::Vector(values) => Value::Array(values.iter().map(Self::to_json).collect()),
+            MoveValue::Struct { fields, .. } => Value::Object(fields_to_json(fields)),
+            MoveValue::Enum {
+                variant, fields, ..
+            } => {
+                let mut object = fields_to_json(fields);
+                object.insert("@variant".to_string(), json!(variant));
+                Value::Object(object)
+            }
+        }
+    }
+}
+
+fn fields_to_json(fields: &[(String, MoveValue)]) -> Map<String, Value> {
+    fields
+        .iter()
+        .map(|(name, value)| (name.clone(), value.to_json()))
+        .collect()
+}
+
+/// Error raised by [`MoveValueDecoder::decode`].
+#[derive(Debug, thiserror::Error)]
+#[error("Cannot decode value of type [{type_}]: {reason}")]
+pub struct DecodeError {
+    pub type_: String,
+    pub reason: String,
+}
+
+/// Decodes BCS bytes into [`MoveValue`]s, using the layouts of the types defined in the packages
+/// added to the decoder.
+#[derive(Default)]
+pub struct MoveValueDecoder {
+    packages: Vec<Package>,
+    /// Index in `packages` of the package each address refers to, see [`Self::add_package`].
+    addresses: HashMap<AccountAddress, usize>,
+}
+
+impl MoveValueDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the layouts of the types of `package`. Types are looked up by the id of the package
+    /// version defining them, as found in type tags, or by the original id of the package, as
+    /// found in bytecode.
+    pub fn add_package(&mut self, package: Package) {
+        let index = self.packages.len();
+        let addresses = package
+            .type_origin_table
+            .values()
+            .flat_map(|m| m.values().copied())
+            .chain(package.module_map.values().map(|m| m.address));
+        for address in addresses {
+            self.addresses.insert(address, index);
+        }
+        self.packages.push(package);
+    }
+
+    pub fn with_package(mut self, package: Package) -> Self {
+        self.add_package(package);
+        self
+    }
+
+    /// Decodes `bytes` as a value of type `type_`, failing if any byte is left over.
+    pub fn decode(&self, type_: &TypeTag, bytes: &[u8]) -> Result<MoveValue, DecodeError> {
+        let mut reader = Reader { bytes, position: 0 };
+        let value = self.decode_value(type_, &mut reader)?;
+        if reader.position != bytes.len() {
+            return Err(decode_error(
+                type_,
+                format!("{} trailing bytes", bytes.len() - reader.position),
+            ));
+        }
+        Ok(value)
+    }
+
+    fn decode_value(&self, type_: &TypeTag, reader: &mut Reader) -> Result<MoveValue, DecodeError> {
+        let eof = |_| decode_error(type_, "unexpected end of input");
+        Ok(match type_ {
+            TypeTag::Bool => match reader.read_u8().map_err(eof)? {
+                0 => MoveValue::Bool(false),
+                1 => MoveValue::Bool(true),
+                v => return Err(decode_error(type_, format!("invalid bool {v}"))),
+            },
+            TypeTag::U8 => MoveValue::U8(reader.read_u8().map_err(eof)?),
+            TypeTag::U16 => MoveValue::U16(u16::from_le_bytes(reader.read().map_err(eof)?)),
+            TypeTag::U32 => MoveValue::U32(u32::from_le_bytes(reader.read().map_err(eof)?)),
+            TypeTag::U64 => MoveValue::U64(u64::from_le_bytes(reader.read().map_err(eof)?)),
+            TypeTag::U128 => MoveValue::U128(u128::from_le_bytes(reader.read().map_err(eof)?)),
+            TypeTag::U256 => MoveValue::U256(U256::from_le_bytes(&reader.read().map_err(eof)?)),
+            TypeTag::Address | TypeTag::Signer => {
+                MoveValue::Address(Address::new(reader.read().map_err(eof)?))
+            }
+            TypeTag::Vector(t) => {
+                let len = reader.read_uleb128().map_err(eof)?;
+                let values = (0..len)
+                    .map(|_| self.decode_value(t, reader))
+                    .collect::<Result<_, _>>()?;
+                MoveValue::Vector(values)
+            }
+            TypeTag::Struct(tag) => self.decode_struct(type_, tag, reader)?,
+        })
+    }
+
+    fn decode_struct(
+        &self,
+        type_: &TypeTag,
+        tag: &StructTag,
+        reader: &mut Reader,
+    ) -> Result<MoveValue, DecodeError> {
+        let eof = |_| decode_error(type_, "unexpected end of input");
+        let address = AccountAddress::new(tag.address.into_inner());
+        match (address, tag.module.as_str(), tag.name.as_str()) {
+            (AccountAddress::ONE, "string" | "ascii", "String") => {
+                let len = reader.read_uleb128().map_err(eof)?;
+                let bytes = reader.read_bytes(len).map_err(eof)?;
+                let value = String::from_utf8(bytes.to_vec())
+                    .map_err(|e| decode_error(type_, e.to_string()))?;
+                return Ok(MoveValue::String(value));
+            }
+            (AccountAddress::ONE, "option", "Option") => {
+                let inner = tag
+                    .type_params
+                    .first()
+                    .ok_or_else(|| decode_error(type_, "missing type argument"))?;
+                return Ok(match reader.read_uleb128().map_err(eof)? {
+                    0 => MoveValue::Option(None),
+                    1 => MoveValue::Option(Some(Box::new(self.decode_value(inner, reader)?))),
+                    len => return Err(decode_error(type_, format!("invalid option length {len}"))),
+                });
+            }
+            (AccountAddress::TWO, "object", "UID" | "ID") => {
+                return Ok(MoveValue::UID(ObjectId::new(reader.read().map_err(eof)?)));
+            }
+            _ => {}
+        }
+
+        let module = self.module(&address, tag.module.as_str()).ok_or_else(|| {
+            decode_error(type_, "no package added to the decoder defines the type")
+        })?;
+        let decode_fields = |fields: &[Field], reader: &mut Reader| {
+            fields
+                .iter()
+                .map(|field| {
+                    let field_type = to_type_tag(&field.type_, &tag.type_params)
+                        .map_err(|reason| decode_error(type_, reason))?;
+                    let value = self.decode_value(&field_type, reader)?;
+                    Ok((field.name.to_string(), value))
+                })
+                .collect::<Result<Vec<_>, DecodeError>>()
+        };
+
+        if let Some((_, move_struct)) = module
+            .structs
+            .iter()
+            .find(|(name, _)| name.as_str() == tag.name.as_str())
+        {
+            return Ok(MoveValue::Struct {
+                type_: tag.clone(),
+                fields: decode_fields(&move_struct.fields, reader)?,
+            });
+        }
+        if let Some((_, move_enum)) = module
+            .enums
+            .iter()
+            .find(|(name, _)| name.as_str() == tag.name.as_str())
+        {
+            let index = reader.read_uleb128().map_err(eof)?;
+            let variant = move_enum
+                .variants
+                .get(index)
+                .ok_or_else(|| decode_error(type_, format!("invalid variant {index}")))?;
+            return Ok(MoveValue::Enum {
+                type_: tag.clone(),
+                variant: variant.name.to_string(),
+                fields: decode_fields(&variant.fields, reader)?,
+            });
+        }
+        Err(decode_error(type_, "type not found in its module"))
+    }
+
+    fn module(&self, address: &AccountAddress, module: &str) -> Option<&Module> {
+        let package = &self.packages[*self.addresses.get(address)?];
+        package.module_map.get(module)
+    }
+}
+
+/// Type tag of a field of type `type_`, in a struct instantiated with `type_args`.
+fn to_type_tag(type_: &Type, type_args: &[TypeTag]) -> Result<TypeTag, String> {
+    Ok(match type_ {
+        Type::Bool => TypeTag::Bool,
+        Type::U8 => TypeTag::U8,
+        Type::U16 => TypeTag::U16,
+        Type::U32 => TypeTag::U32,
+        Type::U64 => TypeTag::U64,
+        Type::U128 => TypeTag::U128,
+        Type::U256 => TypeTag::U256,
+        Type::Address => TypeTag::Address,
+        Type::Signer => TypeTag::Signer,
+        Type::Vector(t) => TypeTag::Vector(Box::new(to_type_tag(t, type_args)?)),
+        Type::Struct {
+            address,
+            module,
+            name,
+            type_arguments,
+        } => TypeTag::Struct(Box::new(StructTag {
+            address: Address::new(address.into_bytes()),
+            module: Identifier::from_str(module.as_str()).map_err(|e| e.to_string())?,
+            name: Identifier::from_str(name.as_str()).map_err(|e| e.to_string())?,
+            type_params: type_arguments
+                .iter()
+                .map(|t| to_type_tag(t, type_args))
+                .collect::<Result<_, _>>()?,
+        })),
+        Type::TypeParameter(index) => type_args
+            .get(*index as usize)
+            .cloned()
+            .ok_or_else(|| format!("missing type argument {index}"))?,
+        Type::Reference(_) | Type::MutableReference(_) => {
+            return Err("references cannot be stored".to_string())
+        }
+    })
+}
+
+fn decode_error(type_: &TypeTag, reason: impl ToString) -> DecodeError {
+    DecodeError {
+        type_: type_.to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+/// End of input reached.
+struct Eof;
+
+impl Reader<'_> {
+    fn read_bytes(&mut self, len: usize) -> Result<&[u8], Eof> {
+        let end = self.position.checked_add(len).ok_or(Eof)?;
+        let bytes = self.bytes.get(self.position..end).ok_or(Eof)?;
+        self.position = end;
+        Ok(bytes)
+    }
+
+    fn read<const N: usize>(&mut self) -> Result<[u8; N], Eof> {
+        let mut bytes = [0; N];
+        bytes.copy_from_slice(self.read_bytes(N)?);
+        Ok(bytes)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, Eof> {
+        Ok(self.read::<1>()?[0])
+    }
+
+    /// Reads a ULEB128 encoded length or variant index.
+    fn read_uleb128(&mut self) -> Result<usize, Eof> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.read_u8()?;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return usize::try_from(value).map_err(|_| Eof);
+            }
+        }
+        Err(Eof)
+    }
+}
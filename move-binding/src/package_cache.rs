@@ -0,0 +1,82 @@
+use crate::error::Error;
+use crate::SuiNetwork;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use sui_sdk_types::Address;
+
+/// Package content as returned by the network, before normalization.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RawPackage {
+    pub package_id: Address,
+    pub version: u64,
+    pub modules: BTreeMap<String, Vec<u8>>,
+    pub type_origins: Vec<TypeOrigin>,
+    pub linkage: Vec<Linkage>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TypeOrigin {
+    pub module: String,
+    pub datatype: String,
+    pub defining_id: Address,
+}
+
+/// Entry of the linkage table of a package, pointing to the version of a dependency the package
+/// was published against.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Linkage {
+    pub original_id: Address,
+    pub upgraded_id: Address,
+    pub version: u64,
+}
+
+/// On-disk cache of fetched packages, keyed by network, package id and version.
+pub struct PackageCache {
+    dir: PathBuf,
+}
+
+impl PackageCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Uses `MOVE_BINDING_CACHE_DIR` if set, `~/.cache/move-binding` otherwise.
+    pub fn from_env() -> Self {
+        let dir = std::env::var_os("MOVE_BINDING_CACHE_DIR")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+            .unwrap_or_else(std::env::temp_dir)
+            .join("move-binding");
+        Self::new(dir)
+    }
+
+    fn package_path(&self, network: &SuiNetwork, package_id: &Address, version: u64) -> PathBuf {
+        self.dir
+            .join(network.name())
+            .join(package_id.to_string())
+            .join(format!("{version}.bcs"))
+    }
+
+    pub fn get(
+        &self,
+        network: &SuiNetwork,
+        package_id: &Address,
+        version: u64,
+    ) -> Option<RawPackage> {
+        let bytes = fs::read(self.package_path(network, package_id, version)).ok()?;
+        // A corrupted entry is treated as a cache miss and will be overwritten.
+        bcs::from_bytes(&bytes).ok()
+    }
+
+    pub fn put(&self, network: &SuiNetwork, package: &RawPackage) -> Result<(), Error> {
+        let path = self.package_path(network, &package.package_id, package.version);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| Error::io(parent, e))?;
+        }
+        let bytes = bcs::to_bytes(package).map_err(|e| Error::Cache(e.to_string()))?;
+        fs::write(&path, bytes).map_err(|e| Error::io(&path, e))?;
+        Ok(())
+    }
+}
@@ -1,3 +1,4 @@
+use crate::error::Error;
 use crate::SuiNetwork;
 use serde_json::Value;
 use std::str::FromStr;
@@ -6,24 +7,67 @@ use sui_sdk_types::Address;
 pub struct PackageIdResolver;
 
 impl PackageIdResolver {
-    pub fn resolve_package_id(
-        network: SuiNetwork,
+    pub async fn resolve_package_id(
+        client: &reqwest::Client,
+        network: &SuiNetwork,
         package: &str,
-    ) -> Result<Address, anyhow::Error> {
+        version: Option<u64>,
+    ) -> Result<Address, Error> {
         Ok(if package.contains("@") || package.contains(".sui") {
-            Self::resolve_mvr_name(package, network.mvr_endpoint())?
+            let name = Self::versioned_mvr_name(package, version)?;
+            let url = network
+                .mvr_endpoint()
+                .ok_or_else(|| Error::MvrNameUnresolved {
+                    name: name.clone(),
+                    reason: format!("MVR is not available on {}", network.name()),
+                })?;
+            Self::resolve_mvr_name(client, &name, url).await?
         } else {
-            Address::from_str(&package)?
+            Address::from_str(&package).map_err(|e| Error::InvalidPackage {
+                package: package.to_string(),
+                reason: e.to_string(),
+            })?
         })
     }
 
-    fn resolve_mvr_name(package: &str, url: &str) -> Result<Address, anyhow::Error> {
-        let client = reqwest::blocking::Client::new();
+    /// Appends the requested version to an MVR name, e.g. `@mvr/core` -> `@mvr/core/3`.
+    fn versioned_mvr_name(package: &str, version: Option<u64>) -> Result<String, Error> {
+        let segments = package.split('/').collect::<Vec<_>>();
+        let name_version = match segments.as_slice() {
+            [_, _, v] => Some(v.parse::<u64>().map_err(|e| Error::InvalidPackage {
+                package: package.to_string(),
+                reason: format!("invalid version [{v}]: {e}"),
+            })?),
+            _ => None,
+        };
+        Ok(match (name_version, version) {
+            (Some(v1), Some(v2)) if v1 != v2 => {
+                return Err(Error::InvalidVersion(format!(
+                    "package [{package}] conflicts with requested version [{v2}]"
+                )))
+            }
+            (None, Some(version)) => format!("{package}/{version}"),
+            _ => package.to_string(),
+        })
+    }
+
+    async fn resolve_mvr_name(
+        client: &reqwest::Client,
+        package: &str,
+        url: &str,
+    ) -> Result<Address, Error> {
         let name = client
             .get(format!("{url}/v1/resolution/{package}"))
-            .send()?;
-        Ok(serde_json::from_value(
-            name.json::<Value>()?["package_id"].clone(),
-        )?)
+            .send()
+            .await
+            .map_err(|e| Error::network(url, e))?;
+        let value = name
+            .json::<Value>()
+            .await
+            .map_err(|e| Error::malformed(url, e))?;
+        serde_json::from_value(value["package_id"].clone()).map_err(|_| Error::MvrNameUnresolved {
+            name: package.to_string(),
+            reason: format!("unexpected response {value}"),
+        })
     }
 }
@@ -1,90 +1,663 @@
+use crate::error::Error;
+use crate::json_rpc;
+use crate::lockfile::Lockfile;
+use crate::package_cache::{Linkage, PackageCache, RawPackage, TypeOrigin};
 use crate::package_id_resolver::PackageIdResolver;
 use crate::SuiNetwork;
 use fastcrypto::encoding::{Base64, Encoding};
+use move_binary_format::file_format::{Bytecode, FunctionDefinitionIndex, SignatureToken};
 use move_binary_format::normalized::Module;
 use move_binary_format::CompiledModule;
+use move_bytecode_source_map::source_map::SourceMap;
 use move_core_types::account_address::AccountAddress;
+use once_cell::sync::Lazy;
 use reqwest::header::CONTENT_TYPE;
 use serde_json::{json, Value};
-use std::collections::{BTreeMap, HashMap};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::future::Future;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use sui_sdk_types::Address;
+use tokio::runtime::Runtime;
 
 pub trait ModuleProvider {
-    fn get_package(&self, package_id: &str) -> Result<Package, anyhow::Error>;
+    /// Fetches `package` at the given version, or at its latest version if `version` is `None`.
+    fn get_package(&self, package_id: &str, version: Option<u64>) -> Result<Package, Error>;
 }
 
-pub struct MoveModuleProvider {
+/// Async counterpart of [`ModuleProvider`], for fetching packages from async code.
+pub trait AsyncModuleProvider {
+    /// Fetches `package` at the given version, or at its latest version if `version` is `None`.
+    fn get_package(
+        &self,
+        package_id: &str,
+        version: Option<u64>,
+    ) -> impl Future<Output = Result<Package, Error>> + Send;
+}
+
+/// Client shared by the async providers created with [`AsyncMoveModuleProvider::new`], so that
+/// they reuse the same connection pool.
+static SHARED_CLIENT: Lazy<reqwest::Client> = Lazy::new(reqwest::Client::new);
+
+/// Runtime driving the requests of the blocking providers, along with their own client: the
+/// connections of a client are bound to the runtime they were opened on.
+static BLOCKING_RUNTIME: Lazy<(Runtime, reqwest::Client)> = Lazy::new(|| {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .enable_all()
+        .build()
+        .expect("cannot start the package fetching runtime");
+    (runtime, reqwest::Client::new())
+});
+
+/// API of a network's services packages are fetched from.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub enum Backend {
+    #[default]
+    GraphQl,
+    JsonRpc,
+}
+
+impl FromStr for Backend {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_lowercase().as_str() {
+            "graphql" => Backend::GraphQl,
+            "jsonrpc" | "json-rpc" => Backend::JsonRpc,
+            _ => return Err(Error::UnknownBackend(s.to_string())),
+        })
+    }
+}
+
+/// Fetches packages from the GraphQL or JSON-RPC service of a network.
+#[derive(Clone)]
+pub struct AsyncMoveModuleProvider {
     network: SuiNetwork,
+    backend: Backend,
+    client: reqwest::Client,
 }
 
-impl MoveModuleProvider {
+impl AsyncMoveModuleProvider {
     pub fn new(network: SuiNetwork) -> Self {
-        Self { network }
+        Self::with_client(network, SHARED_CLIENT.clone())
     }
-}
 
-impl ModuleProvider for MoveModuleProvider {
-    fn get_package(&self, package: &str) -> Result<Package, anyhow::Error> {
-        let package_id = PackageIdResolver::resolve_package_id(self.network, package)?;
-        let client = reqwest::blocking::Client::new();
+    pub fn with_client(network: SuiNetwork, client: reqwest::Client) -> Self {
+        Self {
+            network,
+            backend: Backend::default(),
+            client,
+        }
+    }
+
+    pub fn with_backend(mut self, backend: Backend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Resolves `package` and fetches its content.
+    pub(crate) async fn fetch_raw_package(
+        &self,
+        package: &str,
+        version: Option<u64>,
+    ) -> Result<RawPackage, Error> {
+        let package_id =
+            PackageIdResolver::resolve_package_id(&self.client, &self.network, package, version)
+                .await?;
+        match self.backend {
+            Backend::GraphQl => self.fetch_package(package_id, version).await,
+            Backend::JsonRpc => {
+                let url = self
+                    .network
+                    .json_rpc()
+                    .ok_or_else(|| Error::BackendUnavailable {
+                        backend: "JSON-RPC".to_string(),
+                        network: self.network.name(),
+                    })?;
+                json_rpc::fetch_package(&self.client, url, package_id, version).await
+            }
+        }
+    }
+
+    async fn fetch_package(
+        &self,
+        package_id: Address,
+        version: Option<u64>,
+    ) -> Result<RawPackage, Error> {
+        let url = self.network.gql();
+        let version_arg = version
+            .map(|version| format!(", version: {version}"))
+            .unwrap_or_default();
         let request = format!(
-            r#"{{package(address: "{package_id}") {{moduleBcs, typeOrigins{{module, struct, definingId}}, version}}}}"#
+            r#"{{package(address: "{package_id}"{version_arg}) {{moduleBcs, typeOrigins{{module, struct, definingId}}, linkage{{originalId, upgradedId, version}}, version}}}}"#
         );
-        let res = client
-            .post(self.network.gql())
+        let res = self
+            .client
+            .post(url)
             .header(CONTENT_TYPE, "application/json")
             .json(&json!({
                 "query": request,
                 "variables": Value::Null
             }))
             .send()
-            .ok()
-            .expect("Error fetching package from Sui GQL.");
+            .await
+            .map_err(|e| Error::network(url, e))?;
+
+        let value = res
+            .json::<Value>()
+            .await
+            .map_err(|e| Error::malformed(url, e))?;
+        if let Some(errors) = value.get("errors") {
+            return Err(Error::malformed(url, errors));
+        }
+        let package = &value["data"]["package"];
+        if package.is_null() {
+            let version = version
+                .map(|v| format!(" at version {v}"))
+                .unwrap_or_default();
+            return Err(Error::PackageNotFound(format!(
+                "[{package_id}]{version} on {}",
+                self.network.name()
+            )));
+        }
+
+        let module_bcs: String = serde_json::from_value(package["moduleBcs"].clone())
+            .map_err(|e| Error::malformed(url, format!("invalid moduleBcs: {e}")))?;
+        let module_bytes = Base64::decode(&module_bcs)
+            .map_err(|e| Error::malformed(url, format!("invalid moduleBcs: {e}")))?;
+        let modules: BTreeMap<String, Vec<u8>> = bcs::from_bytes(&module_bytes)
+            .map_err(|e| Error::malformed(url, format!("invalid moduleBcs: {e}")))?;
+
+        let type_origin_table: Vec<Value> = serde_json::from_value(package["typeOrigins"].clone())
+            .map_err(|e| Error::malformed(url, format!("invalid typeOrigins: {e}")))?;
 
-        let value = res.json::<Value>().unwrap();
-        let module_bcs: String =
-            serde_json::from_value(value["data"]["package"]["moduleBcs"].clone()).unwrap();
-        let module_bytes = Base64::decode(&module_bcs).unwrap();
-        let module_map: BTreeMap<String, Vec<u8>> = bcs::from_bytes(&module_bytes).unwrap();
+        let type_origins = type_origin_table
+            .iter()
+            .map(|v| {
+                let field = |name: &str| {
+                    v[name].as_str().ok_or_else(|| {
+                        Error::malformed(url, format!("missing [{name}] in type origin {v}"))
+                    })
+                };
+                Ok(TypeOrigin {
+                    module: field("module")?.to_string(),
+                    datatype: field("struct")?.to_string(),
+                    defining_id: Address::from_str(field("definingId")?)
+                        .map_err(|e| Error::malformed(url, e))?,
+                })
+            })
+            .collect::<Result<_, Error>>()?;
 
-        let module_map = module_map
+        let linkage_table: Vec<Value> = serde_json::from_value(package["linkage"].clone())
+            .map_err(|e| Error::malformed(url, format!("invalid linkage: {e}")))?;
+
+        let linkage = linkage_table
             .iter()
-            .map(|(name, bytes)| {
-                let module = CompiledModule::deserialize_with_defaults(bytes)?;
-                let normalized = Module::new(&module);
-                Ok::<_, anyhow::Error>((name.clone(), normalized))
+            .map(|v| {
+                let address = |name: &str| {
+                    let address = v[name].as_str().ok_or_else(|| {
+                        Error::malformed(url, format!("missing [{name}] in linkage {v}"))
+                    })?;
+                    Address::from_str(address).map_err(|e| Error::malformed(url, e))
+                };
+                Ok(Linkage {
+                    original_id: address("originalId")?,
+                    upgraded_id: address("upgradedId")?,
+                    version: v["version"].as_u64().ok_or_else(|| {
+                        Error::malformed(url, format!("missing [version] in linkage {v}"))
+                    })?,
+                })
             })
-            .collect::<Result<_, _>>()?;
+            .collect::<Result<_, Error>>()?;
+
+        let version = serde_json::from_value(package["version"].clone())
+            .map_err(|e| Error::malformed(url, format!("invalid version: {e}")))?;
 
-        let type_origin_table: Vec<Value> =
-            serde_json::from_value(value["data"]["package"]["typeOrigins"].clone())?;
+        Ok(RawPackage {
+            package_id,
+            version,
+            modules,
+            type_origins,
+            linkage,
+        })
+    }
+}
 
-        let type_origin_table = type_origin_table.iter().fold(
+impl AsyncModuleProvider for AsyncMoveModuleProvider {
+    async fn get_package(&self, package: &str, version: Option<u64>) -> Result<Package, Error> {
+        Package::try_from(self.fetch_raw_package(package, version).await?)
+    }
+}
+
+/// Blocking provider, fetching packages with [`AsyncMoveModuleProvider`] on a runtime of its
+/// own. It must not be used from async code, use [`AsyncMoveModuleProvider`] instead.
+pub struct MoveModuleProvider {
+    network: SuiNetwork,
+    backend: Backend,
+    cache: Option<PackageCache>,
+    lockfile: Option<Lockfile>,
+}
+
+impl MoveModuleProvider {
+    pub fn new(network: SuiNetwork) -> Self {
+        Self {
+            network,
+            backend: Backend::default(),
+            cache: None,
+            lockfile: None,
+        }
+    }
+
+    pub fn with_backend(mut self, backend: Backend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Consults the given cache before fetching packages from the network.
+    pub fn with_cache(mut self, cache: PackageCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Records fetched packages in the given lockfile, and rejects packages whose content
+    /// differs from their existing lockfile entry.
+    pub fn with_lockfile(mut self, lockfile: Lockfile) -> Self {
+        self.lockfile = Some(lockfile);
+        self
+    }
+
+    fn fetch_raw_package(&self, package: &str, version: Option<u64>) -> Result<RawPackage, Error> {
+        let (runtime, client) = &*BLOCKING_RUNTIME;
+        let provider = AsyncMoveModuleProvider::with_client(self.network.clone(), client.clone())
+            .with_backend(self.backend);
+        runtime.block_on(provider.fetch_raw_package(package, version))
+    }
+}
+
+impl ModuleProvider for MoveModuleProvider {
+    fn get_package(&self, package: &str, version: Option<u64>) -> Result<Package, Error> {
+        let locked = match &self.lockfile {
+            Some(lockfile) => lockfile.get(&self.network, package, version)?,
+            None => None,
+        };
+
+        // Packages are only cached by id and version, so only locked packages and packages
+        // pinned to a version by id can be resolved without the network.
+        let resolved = match (&locked, version) {
+            (Some(locked), _) => Some((locked.package_id, locked.version)),
+            (None, Some(version)) => Address::from_str(package).ok().map(|id| (id, version)),
+            (None, None) => None,
+        };
+        let cached = match (&self.cache, resolved) {
+            (Some(cache), Some((package_id, version))) => {
+                cache.get(&self.network, &package_id, version)
+            }
+            _ => None,
+        };
+
+        let raw = match cached {
+            Some(raw) => raw,
+            None => {
+                // Locked packages are fetched at their locked version, so that an upgrade of the
+                // package does not change the build.
+                let raw = match &locked {
+                    Some(locked) => self
+                        .fetch_raw_package(&locked.package_id.to_string(), Some(locked.version))?,
+                    None => self.fetch_raw_package(package, version)?,
+                };
+                if let Some(cache) = &self.cache {
+                    cache.put(&self.network, &raw)?;
+                }
+                raw
+            }
+        };
+
+        if let Some(lockfile) = &self.lockfile {
+            match &locked {
+                Some(locked) => lockfile.verify(package, locked, &raw)?,
+                None => lockfile.insert(&self.network, package, version, &raw)?,
+            }
+        }
+
+        Package::try_from(raw)
+    }
+}
+
+pub struct Package {
+    /// Id of the fetched version of the package, which calls are made to. The modules keep the
+    /// original id of the package, which its types are referred to by.
+    pub package_id: AccountAddress,
+    pub module_map: BTreeMap<String, Module>,
+    pub type_origin_table: HashMap<String, HashMap<String, AccountAddress>>,
+    pub version: u64,
+    /// Source level names by module, from the module's source map, or only the names of its
+    /// clever errors for modules without one.
+    pub source_names: HashMap<String, SourceNames>,
+    /// Packages the package depends on, directly or not.
+    pub dependencies: Vec<PackageDependency>,
+}
+
+pub struct PackageDependency {
+    /// Id of the first version of the dependency, which the package's bytecode refers to.
+    pub original_id: AccountAddress,
+    /// Dependency package, as accepted by the provider the package comes from.
+    pub package: String,
+    /// Name of the dependency, if known.
+    pub name: Option<String>,
+}
+
+/// Names of a module erased from its bytecode, recovered from the module's source map, or from
+/// its clever errors.
+#[derive(Default)]
+pub struct SourceNames {
+    pub functions: HashMap<String, FunctionNames>,
+    /// Constant names keyed by their index in the module's constant pool.
+    pub constants: BTreeMap<u16, String>,
+}
+
+pub struct FunctionNames {
+    pub type_parameters: Vec<String>,
+    pub parameters: Vec<String>,
+}
+
+impl SourceNames {
+    fn new(module: &CompiledModule, source_map: &SourceMap) -> Self {
+        let functions = module
+            .function_defs()
+            .iter()
+            .enumerate()
+            .filter_map(|(i, def)| {
+                let handle = module.function_handle_at(def.function);
+                let name = module.identifier_at(handle.name).to_string();
+                let function_map = source_map
+                    .get_function_source_map(FunctionDefinitionIndex(i as u16))
+                    .ok()?;
+                let names = FunctionNames {
+                    type_parameters: function_map
+                        .type_parameters
+                        .iter()
+                        .map(|(name, _)| name.to_string())
+                        .collect(),
+                    parameters: function_map
+                        .parameters
+                        .iter()
+                        .map(|(name, _)| name.to_string())
+                        .collect(),
+                };
+                Some((name, names))
+            })
+            .collect();
+        let constants = source_map
+            .constant_map
+            .iter()
+            .map(|(name, index)| (*index, name.0.to_string()))
+            .collect();
+        Self {
+            functions,
+            constants,
+        }
+    }
+
+    /// Names of the clever error constants of a module without source map: the clever abort
+    /// codes of its bytecode hold the pool index of the error constant, and of a constant with
+    /// its name.
+    fn from_clever_errors(module: &CompiledModule) -> Self {
+        let pool = module.constant_pool();
+        let constants = module
+            .function_defs()
+            .iter()
+            .filter_map(|def| def.code.as_ref())
+            .flat_map(|code| &code.code)
+            .filter_map(|instruction| match instruction {
+                Bytecode::LdU64(code) if code & (1 << 63) != 0 => Some(*code),
+                _ => None,
+            })
+            .filter_map(|code| {
+                let identifier_index = (code >> 16) as u16;
+                let constant_index = code as u16;
+                let identifier = pool.get(identifier_index as usize)?;
+                pool.get(constant_index as usize)?;
+                if identifier.type_ != SignatureToken::Vector(Box::new(SignatureToken::U8)) {
+                    return None;
+                }
+                let name = bcs::from_bytes::<Vec<u8>>(&identifier.data).ok()?;
+                Some((constant_index, String::from_utf8(name).ok()?))
+            })
+            .collect();
+        Self {
+            functions: HashMap::new(),
+            constants,
+        }
+    }
+}
+
+impl TryFrom<RawPackage> for Package {
+    type Error = Error;
+
+    fn try_from(raw: RawPackage) -> Result<Self, Self::Error> {
+        let mut module_map = BTreeMap::new();
+        let mut source_names = HashMap::new();
+        for (name, bytes) in &raw.modules {
+            let compiled = deserialize_module(name, bytes)?;
+            let names = SourceNames::from_clever_errors(&compiled);
+            if !names.constants.is_empty() {
+                source_names.insert(name.clone(), names);
+            }
+            module_map.insert(name.clone(), Module::new(&compiled));
+        }
+
+        let type_origin_table = raw.type_origins.iter().fold(
             HashMap::new(),
-            |mut results: HashMap<String, HashMap<String, AccountAddress>>, v| {
-                let module = v["module"].as_str().unwrap();
-                let struct_ = v["struct"].as_str().unwrap();
-                let defining_id = v["definingId"].as_str().unwrap();
-                results.entry(module.to_string()).or_default().insert(
-                    struct_.to_string(),
-                    AccountAddress::from_str(defining_id).unwrap(),
+            |mut results: HashMap<String, HashMap<String, AccountAddress>>, origin| {
+                results.entry(origin.module.clone()).or_default().insert(
+                    origin.datatype.clone(),
+                    AccountAddress::new(origin.defining_id.into_inner()),
                 );
                 results
             },
         );
 
-        let version = serde_json::from_value(value["data"]["package"]["version"].clone())?;
+        let dependencies = raw
+            .linkage
+            .iter()
+            .map(|linkage| PackageDependency {
+                original_id: AccountAddress::new(linkage.original_id.into_inner()),
+                package: linkage.upgraded_id.to_string(),
+                name: None,
+            })
+            .collect();
 
         Ok(Package {
+            package_id: AccountAddress::new(raw.package_id.into_inner()),
             module_map,
             type_origin_table,
-            version,
+            version: raw.version,
+            source_names,
+            dependencies,
         })
     }
 }
 
-pub struct Package {
-    pub module_map: BTreeMap<String, Module>,
-    pub type_origin_table: HashMap<String, HashMap<String, AccountAddress>>,
-    pub version: u64,
+/// Reads compiled Move packages from a local build directory, e.g. `build/<pkg>` as produced
+/// by `sui move build`, instead of fetching them from the network.
+pub struct LocalModuleProvider {
+    build_dir: PathBuf,
+    read_files: RefCell<BTreeSet<PathBuf>>,
+}
+
+impl LocalModuleProvider {
+    pub fn new(build_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            build_dir: build_dir.into(),
+            read_files: RefCell::default(),
+        }
+    }
+
+    /// Bytecode and source map files read so far, for the callers to rebuild when they change.
+    pub fn read_files(&self) -> Vec<PathBuf> {
+        self.read_files.borrow().iter().cloned().collect()
+    }
+
+    fn root_name(&self) -> Option<&str> {
+        self.build_dir.file_name().and_then(|name| name.to_str())
+    }
+
+    /// Directories holding the `.mv` files of the root package and each of its dependencies,
+    /// keyed by package name.
+    fn package_dirs(&self) -> Result<BTreeMap<String, PathBuf>, Error> {
+        let bytecode_dir = self.build_dir.join("bytecode_modules");
+        let mut dirs = BTreeMap::new();
+        if let Some(name) = self.root_name() {
+            dirs.insert(name.to_string(), bytecode_dir.clone());
+        }
+        let deps_dir = bytecode_dir.join("dependencies");
+        if deps_dir.is_dir() {
+            let io_error = |e| Error::io(&deps_dir, e);
+            for entry in fs::read_dir(&deps_dir).map_err(io_error)? {
+                let entry = entry.map_err(io_error)?;
+                if entry.file_type().map_err(io_error)?.is_dir() {
+                    dirs.insert(
+                        entry.file_name().to_string_lossy().to_string(),
+                        entry.path(),
+                    );
+                }
+            }
+        }
+        Ok(dirs)
+    }
+
+    /// Reads the modules in `dir`, along with the source names of the modules shipping a
+    /// source map in the matching `source_maps` directory.
+    fn read_modules(&self, dir: &Path) -> Result<LocalPackage, Error> {
+        let source_map_dir = dir
+            .strip_prefix(self.build_dir.join("bytecode_modules"))
+            .map(|relative| self.build_dir.join("source_maps").join(relative))
+            .ok();
+
+        let mut package = LocalPackage::default();
+        for entry in fs::read_dir(dir).map_err(|e| Error::io(dir, e))? {
+            let path = entry.map_err(|e| Error::io(dir, e))?.path();
+            if path.extension().is_some_and(|ext| ext == "mv") {
+                let bytes = fs::read(&path).map_err(|e| Error::io(&path, e))?;
+                self.read_files.borrow_mut().insert(path.clone());
+                let name = path.file_stem().unwrap_or_default().to_string_lossy();
+                let compiled = deserialize_module(&name, &bytes)?;
+                let module = Module::new(&compiled);
+
+                let source_map = source_map_dir
+                    .as_ref()
+                    .map(|source_map_dir| source_map_dir.join(format!("{name}.mvsm")))
+                    .and_then(|path| {
+                        let bytes = fs::read(&path).ok()?;
+                        self.read_files.borrow_mut().insert(path);
+                        Some(bytes)
+                    })
+                    .and_then(|bytes| bcs::from_bytes::<SourceMap>(&bytes).ok());
+                let names = match source_map {
+                    Some(source_map) => SourceNames::new(&compiled, &source_map),
+                    None => SourceNames::from_clever_errors(&compiled),
+                };
+                package.source_names.insert(module.name.to_string(), names);
+                package.module_map.insert(module.name.to_string(), module);
+            }
+        }
+        Ok(package)
+    }
+}
+
+impl ModuleProvider for LocalModuleProvider {
+    /// `package` is either empty (the root package of the build directory), the name of a
+    /// package in the build directory (e.g. `Sui`), or the address of one of them.
+    fn get_package(&self, package: &str, version: Option<u64>) -> Result<Package, Error> {
+        if let Some(version) = version {
+            return Err(Error::InvalidVersion(format!(
+                "cannot select version [{version}] of a package read from a build directory"
+            )));
+        }
+        let not_found = || Error::PackageNotFound(format!("[{package}] in {:?}", self.build_dir));
+        let mut packages = self
+            .package_dirs()?
+            .into_iter()
+            .map(|(name, dir)| Ok((name, self.read_modules(&dir)?)))
+            .collect::<Result<BTreeMap<_, _>, Error>>()?;
+
+        let name = if package.is_empty() {
+            self.root_name().ok_or_else(not_found)?.to_string()
+        } else if packages.contains_key(package) {
+            package.to_string()
+        } else {
+            let address = AccountAddress::from_str(package).map_err(|e| Error::InvalidPackage {
+                package: package.to_string(),
+                reason: format!("not a package name or address in the build directory: {e}"),
+            })?;
+            packages
+                .iter()
+                .find(|(_, local)| local.module_map.values().any(|m| m.address == address))
+                .map(|(name, _)| name.clone())
+                .ok_or_else(not_found)?
+        };
+        let LocalPackage {
+            module_map,
+            source_names,
+        } = packages.remove(&name).ok_or_else(not_found)?;
+
+        let package_id = match module_map.values().next() {
+            Some(module) => module.address,
+            None => return Err(not_found()),
+        };
+
+        // The build directory holds every package of the build, which the package may or may
+        // not depend on.
+        let dependencies = packages
+            .into_iter()
+            .filter_map(|(name, local)| {
+                let original_id = local.module_map.values().next()?.address;
+                Some(PackageDependency {
+                    original_id,
+                    package: name.clone(),
+                    name: Some(name),
+                })
+            })
+            .collect();
+
+        // Packages built locally have not been upgraded, every type originates from the
+        // package itself.
+        let type_origin_table = module_map
+            .iter()
+            .map(|(name, module)| {
+                let origins = module
+                    .structs
+                    .keys()
+                    .chain(module.enums.keys())
+                    .map(|type_name| (type_name.to_string(), module.address))
+                    .collect();
+                (name.clone(), origins)
+            })
+            .collect();
+
+        Ok(Package {
+            package_id,
+            module_map,
+            type_origin_table,
+            version: 1,
+            source_names,
+            dependencies,
+        })
+    }
+}
+
+#[derive(Default)]
+struct LocalPackage {
+    module_map: BTreeMap<String, Module>,
+    source_names: HashMap<String, SourceNames>,
+}
+
+fn deserialize_module(name: &str, bytes: &[u8]) -> Result<CompiledModule, Error> {
+    CompiledModule::deserialize_with_defaults(bytes).map_err(|e| Error::BytecodeDeserialization {
+        module: name.to_string(),
+        reason: e.to_string(),
+    })
 }
@@ -1,17 +1,34 @@
-use crate::move_codegen::BINDING_REGISTRY;
-use itertools::Itertools;
+use crate::error::Error;
+use crate::move_codegen::PackagePaths;
 use move_binary_format::normalized::Type;
 use move_core_types::account_address::AccountAddress;
+use std::collections::BTreeSet;
 
 pub trait ToRustType {
-    fn to_rust_type(&self) -> String;
+    fn to_rust_type(&self, package_paths: &PackagePaths) -> Result<String, Error> {
+        self.to_rust_type_with(package_paths, &[])
+    }
+    /// Same as `to_rust_type`, naming type parameters after `type_params` rather than `T{index}`.
+    fn to_rust_type_with(
+        &self,
+        package_paths: &PackagePaths,
+        type_params: &[String],
+    ) -> Result<String, Error>;
     fn is_ref(&self) -> bool;
-    fn to_arg_type(&self) -> String;
+    fn to_arg_type_with(
+        &self,
+        package_paths: &PackagePaths,
+        type_params: &[String],
+    ) -> Result<String, Error>;
 }
 
 impl ToRustType for Type {
-    fn to_rust_type(&self) -> String {
-        match self {
+    fn to_rust_type_with(
+        &self,
+        package_paths: &PackagePaths,
+        type_params: &[String],
+    ) -> Result<String, Error> {
+        Ok(match self {
             Self::Bool => "bool".to_string(),
             Self::U8 => "u8".to_string(),
             Self::U16 => "u16".to_string(),
@@ -21,18 +38,27 @@ impl ToRustType for Type {
             Self::U256 => "move_types::U256".to_string(),
             Self::Address => "Address".to_string(),
             Self::Signer => "Address".to_string(),
-            t @ Self::Struct { .. } => try_resolve_known_types(t),
+            t @ Self::Struct { .. } => try_resolve_known_types(t, package_paths, type_params)?,
             Self::Vector(t) => {
-                format!("Vec<{}>", t.to_rust_type())
+                format!("Vec<{}>", t.to_rust_type_with(package_paths, type_params)?)
             }
             Self::Reference(t) => {
-                format!("&'static {}", t.to_rust_type())
+                format!(
+                    "&'static {}",
+                    t.to_rust_type_with(package_paths, type_params)?
+                )
             }
             Self::MutableReference(t) => {
-                format!("&'static mut {}", t.to_rust_type())
+                format!(
+                    "&'static mut {}",
+                    t.to_rust_type_with(package_paths, type_params)?
+                )
             }
-            Self::TypeParameter(index) => format!("T{index}"),
-        }
+            Self::TypeParameter(index) => type_params
+                .get(*index as usize)
+                .cloned()
+                .unwrap_or_else(|| format!("T{index}")),
+        })
     }
 
     fn is_ref(&self) -> bool {
@@ -42,20 +68,37 @@ impl ToRustType for Type {
         }
     }
 
-    fn to_arg_type(&self) -> String {
-        match self {
+    fn to_arg_type_with(
+        &self,
+        package_paths: &PackagePaths,
+        type_params: &[String],
+    ) -> Result<String, Error> {
+        Ok(match self {
             Self::Reference(t) => {
-                format!("Ref<'a, {}>", t.to_rust_type())
+                format!(
+                    "Ref<'a, {}>",
+                    t.to_rust_type_with(package_paths, type_params)?
+                )
             }
             Self::MutableReference(t) => {
-                format!("MutRef<'a, {}>", t.to_rust_type())
+                format!(
+                    "MutRef<'a, {}>",
+                    t.to_rust_type_with(package_paths, type_params)?
+                )
             }
-            _ => format!("Arg<{}>", self.to_rust_type()),
-        }
+            _ => format!(
+                "Arg<{}>",
+                self.to_rust_type_with(package_paths, type_params)?
+            ),
+        })
     }
 }
 
-fn try_resolve_known_types(_type: &Type) -> String {
+fn try_resolve_known_types(
+    _type: &Type,
+    package_paths: &PackagePaths,
+    type_params: &[String],
+) -> Result<String, Error> {
     if let Type::Struct {
         address,
         module,
@@ -63,39 +106,105 @@ fn try_resolve_known_types(_type: &Type) -> String {
         type_arguments,
     } = _type
     {
-        match (address, module.as_str(), name.as_str()) {
+        Ok(match (address, module.as_str(), name.as_str()) {
             (&AccountAddress::ONE, "type_name", "TypeName") => "String".to_string(),
             (&AccountAddress::ONE, "string", "String") => "String".to_string(),
             (&AccountAddress::ONE, "ascii", "String") => "String".to_string(),
             (&AccountAddress::ONE, "option", "Option") => {
-                format!("Option<{}>", type_arguments[0].to_rust_type())
+                format!(
+                    "Option<{}>",
+                    type_arguments[0].to_rust_type_with(package_paths, type_params)?
+                )
             }
 
             (&AccountAddress::TWO, "object", "UID") => "ObjectId".to_string(),
             (&AccountAddress::TWO, "object", "ID") => "ObjectId".to_string(),
             _ => {
-                let cache = BINDING_REGISTRY.read().unwrap();
-
-                let package_path =  cache.get(address).cloned();
-                drop(cache); // Release read lock
-
-                let type_ = if let Some(package_path) = package_path{
-                    format!("{package_path}::{module}::{name}")
-                }else{
-                    format!("{module}::{name}")
-                };
+                let package_path =
+                    package_paths
+                        .get(address)
+                        .ok_or_else(|| Error::MissingDependency {
+                            type_: format!("{}::{module}::{name}", address.to_hex_literal()),
+                        })?;
+                let type_ = format!("{package_path}::{module}::{name}");
 
                 if type_arguments.is_empty() {
                     type_
                 } else {
                     format!(
                         "{type_}<{}>",
-                        type_arguments.iter().map(|ty| ty.to_rust_type()).join(", ")
+                        type_arguments
+                            .iter()
+                            .map(|ty| ty.to_rust_type_with(package_paths, type_params))
+                            .collect::<Result<Vec<_>, _>>()?
+                            .join(", ")
                     )
                 }
             }
-        }
+        })
     } else {
         unreachable!()
     }
 }
+
+/// Collects the addresses of the packages defining the types referenced by `t`, except for the
+/// types mapped to Rust types by `try_resolve_known_types`.
+pub(crate) fn collect_packages(t: &Type, packages: &mut BTreeSet<AccountAddress>) {
+    match t {
+        Type::Struct {
+            address,
+            module,
+            name,
+            type_arguments,
+        } => {
+            let builtin = matches!(
+                (address, module.as_str(), name.as_str()),
+                (&AccountAddress::ONE, "type_name", "TypeName")
+                    | (&AccountAddress::ONE, "string", "String")
+                    | (&AccountAddress::ONE, "ascii", "String")
+                    | (&AccountAddress::ONE, "option", "Option")
+                    | (&AccountAddress::TWO, "object", "UID")
+                    | (&AccountAddress::TWO, "object", "ID")
+            );
+            if !builtin {
+                packages.insert(*address);
+            }
+            type_arguments
+                .iter()
+                .for_each(|t| collect_packages(t, packages));
+        }
+        Type::Vector(t) | Type::Reference(t) | Type::MutableReference(t) => {
+            collect_packages(t, packages)
+        }
+        _ => {}
+    }
+}
+
+/// Collects the instantiations of generic structs referenced by `t` whose type arguments are
+/// all known, e.g. `Coin<SUI>` in `vector<Coin<SUI>>`.
+pub(crate) fn collect_instantiations<'a>(t: &'a Type, instantiations: &mut Vec<&'a Type>) {
+    match t {
+        Type::Struct { type_arguments, .. } => {
+            if !type_arguments.is_empty() && is_concrete(t) && !instantiations.contains(&t) {
+                instantiations.push(t);
+            }
+            type_arguments
+                .iter()
+                .for_each(|t| collect_instantiations(t, instantiations));
+        }
+        Type::Vector(t) | Type::Reference(t) | Type::MutableReference(t) => {
+            collect_instantiations(t, instantiations)
+        }
+        _ => {}
+    }
+}
+
+/// Whether `t` has no type parameters.
+fn is_concrete(t: &Type) -> bool {
+    match t {
+        Type::TypeParameter(_) => false,
+        Type::Struct { type_arguments, .. } => type_arguments.iter().all(is_concrete),
+        Type::Vector(t) | Type::Reference(t) | Type::MutableReference(t) => is_concrete(t),
+        _ => true,
+    }
+}
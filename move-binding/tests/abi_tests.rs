@@ -0,0 +1,98 @@
+use move_binding::abi::{AbiError, DynamicArg, PackageAbi, PureValue};
+use move_binding::package_provider::{ModuleProvider, MoveModuleProvider};
+use move_binding::SuiNetwork;
+use std::str::FromStr;
+use sui_sdk_types::{Address, Identifier, StructTag, TypeTag};
+use sui_transaction_builder::TransactionBuilder;
+
+fn sui() -> TypeTag {
+    TypeTag::Struct(Box::new(StructTag {
+        address: Address::TWO,
+        module: Identifier::from_str("sui").unwrap(),
+        name: Identifier::from_str("SUI").unwrap(),
+        type_params: vec![],
+    }))
+}
+
+fn sui_framework() -> PackageAbi {
+    let provider = MoveModuleProvider::new(SuiNetwork::Mainnet);
+    PackageAbi::new(provider.get_package("0x2", None).unwrap())
+}
+
+#[test]
+fn test_dynamic_move_call() {
+    let abi = sui_framework();
+    assert!(abi.function("coin", "split").is_some());
+
+    let sui = sui();
+    let mut builder = TransactionBuilder::new();
+    let coin = abi
+        .move_call(&mut builder, "coin", "zero", vec![sui.clone()], vec![])
+        .unwrap();
+    abi.move_call(
+        &mut builder,
+        "coin",
+        "destroy_zero",
+        vec![sui],
+        vec![DynamicArg::Argument(coin)],
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_dynamic_move_call_type_check() {
+    let abi = sui_framework();
+    let sui = sui();
+    let mut builder = TransactionBuilder::new();
+
+    // `coin::zero` takes a single type argument.
+    let result = abi.move_call(&mut builder, "coin", "zero", vec![], vec![]);
+    assert!(matches!(result, Err(AbiError::InvalidCall { .. })));
+
+    // `coin::value` takes a coin, not a pure value.
+    let result = abi.move_call(
+        &mut builder,
+        "coin",
+        "value",
+        vec![sui],
+        vec![PureValue::U64(1).into()],
+    );
+    assert!(matches!(result, Err(AbiError::InvalidCall { .. })));
+
+    let result = abi.move_call(&mut builder, "coin", "no_such_function", vec![], vec![]);
+    assert!(matches!(result, Err(AbiError::FunctionNotFound { .. })));
+}
+
+#[test]
+fn test_dynamic_move_call_generic_pure_arg() {
+    let provider = MoveModuleProvider::new(SuiNetwork::Mainnet);
+    let abi = PackageAbi::new(provider.get_package("0x1", None).unwrap());
+    let mut builder = TransactionBuilder::new();
+
+    // The `T` parameter of `vector::singleton<T>(e: T)` is checked as the type argument.
+    abi.move_call(
+        &mut builder,
+        "vector",
+        "singleton",
+        vec![TypeTag::U64],
+        vec![PureValue::U64(1).into()],
+    )
+    .unwrap();
+    abi.move_call(
+        &mut builder,
+        "vector",
+        "singleton",
+        vec![TypeTag::Vector(Box::new(TypeTag::U8))],
+        vec![PureValue::Vector(vec![PureValue::U8(1)]).into()],
+    )
+    .unwrap();
+
+    let result = abi.move_call(
+        &mut builder,
+        "vector",
+        "singleton",
+        vec![TypeTag::U64],
+        vec![PureValue::Bool(true).into()],
+    );
+    assert!(matches!(result, Err(AbiError::InvalidCall { .. })));
+}
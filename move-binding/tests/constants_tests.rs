@@ -0,0 +1,51 @@
+use move_binary_format::normalized::{Constant, Type};
+use move_binding::move_codegen::MoveCodegen;
+use move_binding::package_provider::SourceNames;
+use quote::quote;
+use std::collections::BTreeMap;
+
+fn constant<T: serde::Serialize>(type_: Type, value: &T) -> Constant {
+    Constant {
+        type_,
+        data: bcs::to_bytes(value).unwrap(),
+    }
+}
+
+fn generate_constants(datatype_names: &[&str]) -> String {
+    let constants = vec![
+        constant(Type::U64, &1u64),
+        constant(Type::U64, &100u64),
+        constant(Type::Vector(Box::new(Type::U8)), &b"Not the owner".to_vec()),
+    ];
+    let source_names = SourceNames {
+        constants: BTreeMap::from([
+            (0, "ENotOwner".to_string()),
+            (1, "MAX_ITEMS".to_string()),
+            (2, "EInvalidOwner".to_string()),
+        ]),
+        ..Default::default()
+    };
+    let tokens = MoveCodegen::create_constants(&constants, Some(&source_names), datatype_names);
+    prettyplease::unparse(&syn::parse2(quote! {#(#tokens)*}).unwrap())
+}
+
+#[test]
+fn test_create_constants() {
+    let code = generate_constants(&[]);
+    assert!(code.contains("pub const ENotOwner: u64 = 1u64;"));
+    assert!(code.contains("pub const MAX_ITEMS: u64 = 100u64;"));
+    assert!(code.contains("pub const EInvalidOwner: &[u8] = &["));
+    assert!(code.contains("pub enum Error {\n    ENotOwner,\n    EInvalidOwner,\n}"));
+    // Plain codes are matched by value, clever errors by constant index.
+    assert!(code.contains("1u64 => Some(Self::ENotOwner)"));
+    assert!(code.contains("0u16 => Some(Self::ENotOwner)"));
+    assert!(code.contains("2u16 => Some(Self::EInvalidOwner)"));
+    assert!(!code.contains("Self::MAX_ITEMS"));
+}
+
+#[test]
+fn test_create_constants_naming() {
+    assert!(generate_constants(&["Error"]).contains("pub enum AbortError {"));
+    // Without source names the constants are unknown.
+    assert!(MoveCodegen::create_constants(&[constant(Type::U64, &1u64)], None, &[]).is_empty());
+}
@@ -0,0 +1,40 @@
+use move_binding::error::Error;
+use move_binding::lockfile::{LockedPackage, Lockfile};
+use move_binding::package_cache::RawPackage;
+use move_binding::SuiNetwork;
+use std::collections::BTreeMap;
+use sui_sdk_types::Address;
+
+fn package(module_bytes: Vec<u8>) -> RawPackage {
+    RawPackage {
+        package_id: Address::TWO,
+        version: 1,
+        modules: BTreeMap::from([("foo".to_string(), module_bytes)]),
+        type_origins: vec![],
+        linkage: vec![],
+    }
+}
+
+#[test]
+fn test_lockfile_insert() {
+    let dir = tempfile::tempdir().unwrap();
+    let lockfile = Lockfile::new(dir.path().join("move-binding.lock"));
+    let network = SuiNetwork::Mainnet;
+    let fetched = package(vec![1, 2, 3]);
+
+    assert_eq!(lockfile.get(&network, "0x2", None).unwrap(), None);
+    lockfile.insert(&network, "0x2", None, &fetched).unwrap();
+    assert_eq!(
+        lockfile.get(&network, "0x2", None).unwrap(),
+        Some(LockedPackage::new(&fetched).unwrap())
+    );
+
+    // A package locked by a concurrent expansion is verified rather than overwritten.
+    lockfile.insert(&network, "0x2", None, &fetched).unwrap();
+    let result = lockfile.insert(&network, "0x2", None, &package(vec![4]));
+    assert!(matches!(result, Err(Error::Lockfile(_))));
+    assert_eq!(
+        lockfile.get(&network, "0x2", None).unwrap(),
+        Some(LockedPackage::new(&fetched).unwrap())
+    );
+}
@@ -0,0 +1,51 @@
+use move_binding::move_value::{MoveValue, MoveValueDecoder};
+use move_binding::package_provider::{ModuleProvider, MoveModuleProvider};
+use move_binding::SuiNetwork;
+use serde_json::json;
+use std::str::FromStr;
+use sui_sdk_types::{Address, Identifier, ObjectId, StructTag, TypeTag};
+
+fn struct_tag(address: Address, module: &str, name: &str, type_params: Vec<TypeTag>) -> TypeTag {
+    TypeTag::Struct(Box::new(StructTag {
+        address,
+        module: Identifier::from_str(module).unwrap(),
+        name: Identifier::from_str(name).unwrap(),
+        type_params,
+    }))
+}
+
+#[test]
+fn test_decode_coin() {
+    let provider = MoveModuleProvider::new(SuiNetwork::Mainnet);
+    let decoder = MoveValueDecoder::new().with_package(provider.get_package("0x2", None).unwrap());
+
+    let sui = struct_tag(Address::TWO, "sui", "SUI", vec![]);
+    let coin = struct_tag(Address::TWO, "coin", "Coin", vec![sui]);
+    let id = ObjectId::new([7; 32]);
+    let bytes = bcs::to_bytes(&(id, 1_000_000_000u64)).unwrap();
+
+    let value = decoder.decode(&coin, &bytes).unwrap();
+    let MoveValue::Struct { fields, .. } = &value else {
+        panic!("expected a struct, got {value:?}");
+    };
+    assert_eq!(fields[0], ("id".to_string(), MoveValue::UID(id)));
+    assert_eq!(
+        value.to_json(),
+        json!({"id": id.to_string(), "balance": {"value": "1000000000"}})
+    );
+
+    // Trailing bytes are rejected.
+    assert!(decoder.decode(&coin, &[bytes, vec![0]].concat()).is_err());
+}
+
+#[test]
+fn test_decode_builtin_types() {
+    let decoder = MoveValueDecoder::new();
+    let string = struct_tag(Address::ONE, "string", "String", vec![]);
+    let option = struct_tag(Address::ONE, "option", "Option", vec![string]);
+    let type_ = TypeTag::Vector(Box::new(option));
+    let bytes = bcs::to_bytes(&vec![Some("move".to_string()), None]).unwrap();
+
+    let value = decoder.decode(&type_, &bytes).unwrap();
+    assert_eq!(value.to_json(), json!(["move", null]));
+}
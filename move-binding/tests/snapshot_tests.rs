@@ -1,28 +1,155 @@
 use insta::assert_snapshot;
-use move_binding::move_codegen::MoveCodegen;
+use move_binding::error::Error;
+use move_binding::move_codegen::{CodegenOptions, MoveCodegen};
+use move_binding::package_provider::{
+    AsyncModuleProvider, AsyncMoveModuleProvider, Backend, ModuleProvider, MoveModuleProvider,
+    Package,
+};
 use move_binding::SuiNetwork;
 use syn::parse2;
 
 #[test]
 fn test_generate_sui_packages() {
-    test_package(SuiNetwork::Mainnet, "0x1", "move_lib");
-    test_package(SuiNetwork::Mainnet, "0x2", "sui");
-    test_package(SuiNetwork::Mainnet, "0x3", "sui_system");
-    test_package(SuiNetwork::Mainnet, "0xb", "bridge");
+    test_package(SuiNetwork::Mainnet, "0x1", "move_lib", &[]);
+    test_package(SuiNetwork::Mainnet, "0x2", "sui", &["crate::move_lib"]);
+    test_package(
+        SuiNetwork::Mainnet,
+        "0x3",
+        "sui_system",
+        &["crate::move_lib", "crate::sui"],
+    );
+    test_package(
+        SuiNetwork::Mainnet,
+        "0xb",
+        "bridge",
+        &["crate::move_lib", "crate::sui", "crate::sui_system"],
+    );
     test_package(
         SuiNetwork::Testnet,
         "0x8270feb7375eee355e64fdb69c50abb6b5f9393a722883c1cf45f8e26048810a",
         "wal",
+        &["crate::move_lib", "crate::sui"],
     );
     test_package(
         SuiNetwork::Testnet,
         "0xd84704c17fc870b8764832c535aa6b11f21a95cd6f5bb38a9b07d2cf42220c66",
         "walrus",
+        &[
+            "crate::move_lib",
+            "crate::sui",
+            "crate::wal=0x8270feb7375eee355e64fdb69c50abb6b5f9393a722883c1cf45f8e26048810a",
+        ],
+    );
+}
+
+#[test]
+fn test_missing_dependency() {
+    let provider = MoveModuleProvider::new(SuiNetwork::Mainnet);
+    let result = MoveCodegen::expand_with_provider(
+        &provider,
+        "0x3",
+        None,
+        "sui_system",
+        "crate",
+        &CodegenOptions::default(),
+    );
+    assert!(matches!(result, Err(Error::MissingDependency { .. })));
+}
+
+#[test]
+fn test_include_deps() {
+    let provider = MoveModuleProvider::new(SuiNetwork::Mainnet);
+    let options = CodegenOptions {
+        deps: vec!["crate::sui".parse().unwrap()],
+        include_deps: true,
+        ..Default::default()
+    };
+    let ts = MoveCodegen::expand_with_provider(
+        &provider,
+        "@deepbook/core",
+        None,
+        "deepbook",
+        "crate",
+        &options,
+    )
+    .unwrap();
+    let pretty = prettyplease::unparse(&parse2::<syn::File>(ts).unwrap());
+    // The DEEP token package is generated along with DeepBook, the Sui framework is not.
+    assert!(pretty.contains("pub mod deps"));
+    assert!(pretty.contains("crate::deepbook::deps::"));
+    assert!(!pretty.contains("crate::deepbook::deps::sui::"));
+}
+
+#[test]
+fn test_write_package() {
+    let out_dir = tempfile::tempdir().unwrap();
+    let provider = MoveModuleProvider::new(SuiNetwork::Mainnet);
+    let files = MoveCodegen::write_package(
+        &provider,
+        "0x1",
+        None,
+        "move_lib",
+        "crate",
+        &CodegenOptions::default(),
+        out_dir.path(),
+    )
+    .unwrap();
+
+    let package_dir = out_dir.path().join("move_lib");
+    for path in [package_dir.join("mod.rs"), package_dir.join("option.rs")] {
+        assert!(files.contains(&path), "{} was not written", path.display());
+    }
+    for path in files {
+        let content = std::fs::read_to_string(&path).unwrap();
+        syn::parse_file(&content)
+            .unwrap_or_else(|e| panic!("{} does not parse: {e}", path.display()));
+    }
+}
+
+#[tokio::test]
+async fn test_async_provider() {
+    let provider = AsyncMoveModuleProvider::new(SuiNetwork::Mainnet);
+    let package = provider.get_package("0x1", None).await.unwrap();
+    assert!(package.module_map.contains_key("option"));
+}
+
+#[test]
+fn test_json_rpc_backend() {
+    let graphql = MoveModuleProvider::new(SuiNetwork::Mainnet)
+        .get_package("0x2", None)
+        .unwrap();
+    let json_rpc = MoveModuleProvider::new(SuiNetwork::Mainnet)
+        .with_backend(Backend::JsonRpc)
+        .get_package("0x2", None)
+        .unwrap();
+    assert_eq!(
+        graphql.module_map.keys().collect::<Vec<_>>(),
+        json_rpc.module_map.keys().collect::<Vec<_>>()
     );
+    assert_eq!(graphql.type_origin_table, json_rpc.type_origin_table);
+}
+
+#[test]
+fn test_package_id_of_version() {
+    let provider = MoveModuleProvider::new(SuiNetwork::Mainnet);
+    let first = provider.get_package("@deepbook/core", Some(1)).unwrap();
+    let latest = provider.get_package("@deepbook/core", None).unwrap();
+    // Each version has an id of its own, which calls are made to, while the modules of every
+    // version keep the original id.
+    let original_id = |package: &Package| package.module_map.values().next().unwrap().address;
+    assert_eq!(first.package_id, original_id(&first));
+    assert_eq!(original_id(&latest), original_id(&first));
+    assert_ne!(latest.package_id, first.package_id);
 }
 
-fn test_package(network: SuiNetwork, package: &str, alias: &str) {
-    let ts = MoveCodegen::expand(network, package, alias, "crate").unwrap();
+fn test_package(network: SuiNetwork, package: &str, alias: &str, deps: &[&str]) {
+    let options = CodegenOptions {
+        deps: deps.iter().map(|dep| dep.parse().unwrap()).collect(),
+        ..Default::default()
+    };
+    let provider = MoveModuleProvider::new(network);
+    let ts = MoveCodegen::expand_with_provider(&provider, package, None, alias, "crate", &options)
+        .unwrap();
     let file = parse2::<syn::File>(ts.clone()).expect("Failed to parse TokenStream");
     let pretty = prettyplease::unparse(&file);
     assert_snapshot!(package, pretty)
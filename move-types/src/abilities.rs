@@ -0,0 +1,33 @@
+//! Marker traits for the abilities of Move types, bounding the type parameters of the generated
+//! functions like the abilities constraining them in Move.
+use crate::{MoveType, ObjectId, U256};
+use sui_sdk_types::Address;
+
+pub trait Copy: MoveType {}
+
+pub trait Drop: MoveType {}
+
+pub trait Store: MoveType {}
+
+macro_rules! impl_abilities {
+    ($($t:ty),*) => {
+        $(
+            impl Copy for $t {}
+            impl Drop for $t {}
+            impl Store for $t {}
+        )*
+    };
+}
+
+impl_abilities!(bool, u8, u16, u32, u64, u128, U256, Address, String, &str);
+
+// `ObjectId` takes the abilities of `0x2::object::UID`, its `MoveType`.
+impl Store for ObjectId {}
+
+impl<T: Copy> Copy for Vec<T> {}
+impl<T: Drop> Drop for Vec<T> {}
+impl<T: Store> Store for Vec<T> {}
+
+impl<T: Copy> Copy for Option<T> {}
+impl<T: Drop> Drop for Option<T> {}
+impl<T: Store> Store for Option<T> {}
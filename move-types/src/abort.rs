@@ -0,0 +1,29 @@
+/// Code of a `MoveAbort`, see the `from_abort_code` function of the generated `Error` enums.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AbortCode {
+    /// Plain `u64` code passed to `abort` or `assert!`.
+    Code(u64),
+    /// Clever error, raised with a `#[error]` constant.
+    Clever {
+        /// Source line of the abort.
+        line: u16,
+        /// Index in the constant pool of the name of the error constant.
+        identifier_index: u16,
+        /// Index in the constant pool of the error constant.
+        constant_index: u16,
+    },
+}
+
+impl From<u64> for AbortCode {
+    fn from(code: u64) -> Self {
+        // Clever errors set the top bit, followed by 15 reserved bits and three 16-bit fields.
+        if code & (1 << 63) == 0 {
+            return AbortCode::Code(code);
+        }
+        AbortCode::Clever {
+            line: (code >> 32) as u16,
+            identifier_index: (code >> 16) as u16,
+            constant_index: code as u16,
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use crate::error::BindingError;
+use std::ops::{Deref, DerefMut};
+use sui_sdk_types::{Argument, Transaction};
+use sui_transaction_builder::TransactionBuilder;
+
+/// Builder the generated functions and the helpers of this crate add their commands to.
+pub trait PtbBuilder {
+    fn builder(&mut self) -> &mut TransactionBuilder;
+
+    /// Called with each result of a type without `drop`, which must be used by a later command.
+    fn track_result(&mut self, _result: &Argument) {}
+
+    /// Called with each argument passed by value to a command.
+    fn consume(&mut self, _arg: &Argument) {}
+}
+
+impl PtbBuilder for TransactionBuilder {
+    fn builder(&mut self) -> &mut TransactionBuilder {
+        self
+    }
+}
+
+/// Transaction builder checking that every result without `drop` is used by a later command,
+/// as the transaction would otherwise fail at execution. Only the commands added through the
+/// generated functions and the helpers of this crate are tracked, not the commands added to the
+/// underlying [`TransactionBuilder`] directly.
+pub struct CheckedBuilder {
+    builder: TransactionBuilder,
+    unused: Vec<Argument>,
+}
+
+impl CheckedBuilder {
+    pub fn new(builder: TransactionBuilder) -> Self {
+        Self {
+            builder,
+            unused: vec![],
+        }
+    }
+
+    /// Results without `drop` not used by any command so far.
+    pub fn unused_results(&self) -> &[Argument] {
+        &self.unused
+    }
+
+    /// Builds the transaction, failing if a result without `drop` is left unused.
+    pub fn finish(self) -> Result<Transaction, BindingError> {
+        if !self.unused.is_empty() {
+            return Err(BindingError::UnusedResults(self.unused));
+        }
+        self.builder
+            .finish()
+            .map_err(|e| BindingError::Build(e.to_string()))
+    }
+}
+
+impl PtbBuilder for CheckedBuilder {
+    fn builder(&mut self) -> &mut TransactionBuilder {
+        &mut self.builder
+    }
+
+    fn track_result(&mut self, result: &Argument) {
+        self.unused.push(*result);
+    }
+
+    fn consume(&mut self, arg: &Argument) {
+        self.unused.retain(|result| match (result, arg) {
+            // Using the results of a command as a whole uses each of them.
+            (Argument::NestedResult(command, _), Argument::Result(used)) => command != used,
+            _ => result != arg,
+        });
+    }
+}
+
+impl Deref for CheckedBuilder {
+    type Target = TransactionBuilder;
+
+    fn deref(&self) -> &Self::Target {
+        &self.builder
+    }
+}
+
+impl DerefMut for CheckedBuilder {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.builder
+    }
+}
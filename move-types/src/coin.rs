@@ -0,0 +1,76 @@
+use crate::builder::PtbBuilder;
+use crate::functions::{Arg, MutRef};
+use crate::MoveStruct;
+use sui_sdk_types::Argument;
+
+/// Implemented by the generated bindings of `0x2::coin::Coin`, to type the native coin commands
+/// of programmable transactions.
+pub trait CoinType: MoveStruct {}
+
+/// Implemented by the generated bindings of `0x2::coin::Coin<0x2::sui::SUI>`.
+pub trait GasCoin: CoinType {}
+
+/// Coin paying for the gas of the transaction.
+pub fn gas_coin<C: GasCoin>() -> Arg<C> {
+    Arg::Resolved(Argument::Gas)
+}
+
+/// Splits one coin per amount off `coin`.
+#[must_use = "coins have no `drop` ability and must be used by a later command"]
+pub fn split_coins<C: CoinType>(
+    builder: &mut impl PtbBuilder,
+    coin: MutRef<C>,
+    amounts: Vec<Arg<u64>>,
+) -> Vec<Arg<C>> {
+    let coin = coin.resolve_arg(builder.builder());
+    let count = amounts.len() as u16;
+    let amounts = amounts
+        .into_iter()
+        .map(|amount| amount.consume(builder))
+        .collect();
+    let Argument::Result(command) = builder.builder().split_coins(coin, amounts) else {
+        unreachable!("split_coins returns Argument::Result")
+    };
+    (0..count)
+        .map(|i| {
+            let coin = Argument::NestedResult(command, i);
+            builder.track_result(&coin);
+            coin.into()
+        })
+        .collect()
+}
+
+/// Merges `coins` into `coin`.
+pub fn merge_coins<C: CoinType>(
+    builder: &mut impl PtbBuilder,
+    coin: MutRef<C>,
+    coins: Vec<Arg<C>>,
+) {
+    let coin = coin.resolve_arg(builder.builder());
+    let coins = coins
+        .into_iter()
+        .map(|coin| coin.consume(builder))
+        .collect();
+    builder.builder().merge_coins(coin, coins);
+}
+
+/// Merges `coins` into `coin`, then splits one coin per amount off it. Returns the merged coin,
+/// holding the remaining balance, and the split coins.
+#[must_use = "coins have no `drop` ability and must be used by a later command"]
+pub fn merge_and_split<C: CoinType>(
+    builder: &mut impl PtbBuilder,
+    coin: Arg<C>,
+    coins: Vec<Arg<C>>,
+    amounts: Vec<Arg<u64>>,
+) -> (Arg<C>, Vec<Arg<C>>) {
+    let mut coin = Arg::Resolved(coin.resolve_arg(builder.builder()));
+    if !coins.is_empty() {
+        merge_coins(builder, coin.borrow_mut(), coins);
+    }
+    let split = if amounts.is_empty() {
+        vec![]
+    } else {
+        split_coins(builder, coin.borrow_mut(), amounts)
+    };
+    (coin, split)
+}
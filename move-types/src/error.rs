@@ -0,0 +1,15 @@
+/// Errors raised when building transactions with the generated bindings.
+#[derive(Debug, thiserror::Error)]
+pub enum BindingError {
+    #[error("Cannot use an unresolved argument, add it to the transaction with `resolve_arg`")]
+    UnresolvedArg,
+    #[error("Results without `drop` are never used: {0:?}")]
+    UnusedResults(Vec<sui_sdk_types::Argument>),
+    #[error("Cannot build transaction: {0}")]
+    Build(String),
+    #[error("Missing value of field [{field}] of [{type_}]")]
+    MissingField {
+        type_: &'static str,
+        field: &'static str,
+    },
+}
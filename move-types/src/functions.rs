@@ -1,22 +1,109 @@
-use crate::MoveType;
+use crate::abilities;
+use crate::builder::PtbBuilder;
+use crate::error::BindingError;
+use crate::{Key, MoveStruct, MoveType};
 use serde::Serialize;
-use sui_sdk_types::Argument;
+use sui_sdk_types::{Address, Argument, ObjectDigest, ObjectId};
 use sui_transaction_builder::unresolved::Input;
 use sui_transaction_builder::{Serialized, TransactionBuilder};
 
 pub enum Arg<T> {
     Resolved(Argument),
     Raw(T),
+    /// On-chain object, see [`Arg::owned`], [`Arg::shared`] and [`Arg::receiving`].
+    Object(Input),
 }
 
 pub enum Ref<'a, T> {
     Resolved(Argument),
     Raw(&'a T),
+    Object(Input),
 }
 
 pub enum MutRef<'a, T> {
     Resolved(Argument),
     Raw(&'a mut T),
+    Object(Input),
+}
+
+/// Argument of an entry-only function. Entry functions cannot take the results of earlier
+/// commands, so only pure values and objects can be passed.
+pub enum EntryArg<T> {
+    Raw(T),
+    /// On-chain object, see [`EntryArg::owned`], [`EntryArg::shared`] and
+    /// [`EntryArg::receiving`].
+    Object(Input),
+}
+
+/// Implemented by the generated bindings of `0x2::transfer::Receiving`, the type of parameters
+/// taking an object sent to another object.
+pub trait Receiving: MoveStruct {}
+
+impl<T: Key> Arg<T> {
+    /// Object owned by the sender of the transaction.
+    pub fn owned(id: ObjectId, version: u64, digest: ObjectDigest) -> Self {
+        Self::Object(Input::owned(id, version, digest))
+    }
+
+    /// Shared object, always taken mutably as it is passed by value.
+    pub fn shared(id: ObjectId, initial_version: u64) -> Self {
+        Self::Object(Input::shared(id, initial_version, true))
+    }
+}
+
+impl<T: Receiving> Arg<T> {
+    /// Object sent to another object, for `0x2::transfer::Receiving` parameters.
+    pub fn receiving(id: ObjectId, version: u64, digest: ObjectDigest) -> Self {
+        Self::Object(Input::receiving(id, version, digest))
+    }
+}
+
+impl<T: Key> EntryArg<T> {
+    pub fn owned(id: ObjectId, version: u64, digest: ObjectDigest) -> Self {
+        Self::Object(Input::owned(id, version, digest))
+    }
+
+    /// Immutable object, only for parameters taking the object by reference.
+    pub fn immutable(id: ObjectId, version: u64, digest: ObjectDigest) -> Self {
+        Self::Object(Input::immutable(id, version, digest))
+    }
+
+    /// Shared object, which must be `mutable` unless taken by immutable reference.
+    pub fn shared(id: ObjectId, initial_version: u64, mutable: bool) -> Self {
+        Self::Object(Input::shared(id, initial_version, mutable))
+    }
+}
+
+impl<T: Receiving> EntryArg<T> {
+    pub fn receiving(id: ObjectId, version: u64, digest: ObjectDigest) -> Self {
+        Self::Object(Input::receiving(id, version, digest))
+    }
+}
+
+impl<T: Key> Ref<'_, T> {
+    pub fn owned(id: ObjectId, version: u64, digest: ObjectDigest) -> Self {
+        Self::Object(Input::owned(id, version, digest))
+    }
+
+    pub fn immutable(id: ObjectId, version: u64, digest: ObjectDigest) -> Self {
+        Self::Object(Input::immutable(id, version, digest))
+    }
+
+    /// Shared object, read-only unless `mutable` is set.
+    pub fn shared(id: ObjectId, initial_version: u64, mutable: bool) -> Self {
+        Self::Object(Input::shared(id, initial_version, mutable))
+    }
+}
+
+impl<T: Key> MutRef<'_, T> {
+    pub fn owned(id: ObjectId, version: u64, digest: ObjectDigest) -> Self {
+        Self::Object(Input::owned(id, version, digest))
+    }
+
+    /// Shared object, always taken mutably.
+    pub fn shared(id: ObjectId, initial_version: u64) -> Self {
+        Self::Object(Input::shared(id, initial_version, true))
+    }
 }
 
 impl<T: MoveType> From<Argument> for MutRef<'_, T> {
@@ -43,20 +130,41 @@ impl<T: MoveType> From<T> for Arg<T> {
     }
 }
 
+impl<T: MoveType> From<T> for EntryArg<T> {
+    fn from(value: T) -> Self {
+        Self::Raw(value)
+    }
+}
+
 impl<T> Arg<T> {
-    pub fn resolve_arg(self, builder: &mut TransactionBuilder) -> Self
+    /// Adds the value to the inputs of the transaction if it is not already part of it.
+    pub fn resolve_arg(self, builder: &mut TransactionBuilder) -> Argument
     where
         T: ToInput,
     {
         match self {
-            Arg::Raw(value) => Self::Resolved(builder.input(value.to_input())),
-            _ => self,
+            Arg::Resolved(arg) => arg,
+            Arg::Raw(value) => builder.input(value.to_input()),
+            Arg::Object(input) => builder.input(input),
         }
     }
+
+    /// Resolves the argument of a command taking it by value, see [`PtbBuilder::consume`].
+    pub fn consume(self, builder: &mut impl PtbBuilder) -> Argument
+    where
+        T: ToInput,
+    {
+        let arg = self.resolve_arg(builder.builder());
+        builder.consume(&arg);
+        arg
+    }
+    /// Borrowing an unresolved object adds it to the transaction with every use, resolve it
+    /// first to use it several times.
     pub fn borrow(&self) -> Ref<T> {
         match self {
             Arg::Resolved(a) => Ref::Resolved(a.clone()),
             Arg::Raw(p) => Ref::Raw(p),
+            Arg::Object(input) => Ref::Object(input.clone()),
         }
     }
 
@@ -64,58 +172,128 @@ impl<T> Arg<T> {
         match self {
             Arg::Resolved(a) => MutRef::Resolved(a.clone()),
             Arg::Raw(p) => MutRef::Raw(p),
+            Arg::Object(input) => MutRef::Object(input.clone()),
         }
     }
 }
 
 impl<T> Ref<'_, T> {
-    pub fn resolve_arg(self, builder: &mut TransactionBuilder) -> Self
+    pub fn resolve_arg(self, builder: &mut TransactionBuilder) -> Argument
     where
         T: ToInput,
     {
         match self {
-            Ref::Raw(value) => Self::Resolved(builder.input(value.to_input())),
-            _ => self,
+            Ref::Resolved(arg) => arg,
+            Ref::Raw(value) => builder.input(value.to_input()),
+            Ref::Object(input) => builder.input(input),
         }
     }
 }
 
 impl<T> MutRef<'_, T> {
-    pub fn resolve_arg(self, builder: &mut TransactionBuilder) -> Self
+    pub fn resolve_arg(self, builder: &mut TransactionBuilder) -> Argument
     where
         T: ToInput,
     {
         match self {
-            MutRef::Raw(value) => Self::Resolved(builder.input(value.to_input())),
-            _ => self,
+            MutRef::Resolved(arg) => arg,
+            MutRef::Raw(value) => builder.input(value.to_input()),
+            MutRef::Object(input) => builder.input(input),
         }
     }
 }
-impl<T> From<Arg<T>> for Argument {
-    fn from(value: Arg<T>) -> Self {
+
+impl<T> EntryArg<T> {
+    /// Adds the value to the inputs of the transaction.
+    pub fn resolve_arg(self, builder: &mut TransactionBuilder) -> Argument
+    where
+        T: ToInput,
+    {
+        match self {
+            EntryArg::Raw(value) => builder.input(value.to_input()),
+            EntryArg::Object(input) => builder.input(input),
+        }
+    }
+}
+
+/// Raw values and objects have no `Argument` until added to a transaction with `resolve_arg`.
+impl<T> TryFrom<Arg<T>> for Argument {
+    type Error = BindingError;
+
+    fn try_from(value: Arg<T>) -> Result<Self, Self::Error> {
         match value {
-            Arg::Resolved(arg) => arg,
-            Arg::Raw(_) => panic!("Cannot use unresolved arg"),
+            Arg::Resolved(arg) => Ok(arg),
+            Arg::Raw(_) | Arg::Object(_) => Err(BindingError::UnresolvedArg),
         }
     }
 }
-impl<T> From<MutRef<'_, T>> for Argument {
-    fn from(value: MutRef<'_, T>) -> Self {
+impl<T> TryFrom<MutRef<'_, T>> for Argument {
+    type Error = BindingError;
+
+    fn try_from(value: MutRef<'_, T>) -> Result<Self, Self::Error> {
         match value {
-            MutRef::Resolved(arg) => arg,
-            MutRef::Raw(_) => panic!("Cannot use unresolved arg"),
+            MutRef::Resolved(arg) => Ok(arg),
+            MutRef::Raw(_) | MutRef::Object(_) => Err(BindingError::UnresolvedArg),
         }
     }
 }
-impl<T> From<Ref<'_, T>> for Argument {
-    fn from(value: Ref<'_, T>) -> Self {
+impl<T> TryFrom<Ref<'_, T>> for Argument {
+    type Error = BindingError;
+
+    fn try_from(value: Ref<'_, T>) -> Result<Self, Self::Error> {
         match value {
-            Ref::Resolved(arg) => arg,
-            Ref::Raw(_) => panic!("Cannot use unresolved arg"),
+            Ref::Resolved(arg) => Ok(arg),
+            Ref::Raw(_) | Ref::Object(_) => Err(BindingError::UnresolvedArg),
         }
     }
 }
 
+/// Transfers `objects` to `recipient`, only objects with `store` can be transferred by a
+/// transaction command.
+pub fn transfer_objects<T: Key + abilities::Store>(
+    builder: &mut impl PtbBuilder,
+    objects: Vec<Arg<T>>,
+    recipient: Arg<Address>,
+) {
+    let objects = objects
+        .into_iter()
+        .map(|object| object.consume(builder))
+        .collect();
+    let recipient = recipient.consume(builder);
+    builder.builder().transfer_objects(objects, recipient);
+}
+
+/// Builds a Move vector of `elements` with `drop`, which needs not be used.
+pub fn make_move_vec<T: abilities::Drop>(
+    builder: &mut impl PtbBuilder,
+    elements: Vec<Arg<T>>,
+) -> Arg<Vec<T>> {
+    add_make_move_vec(builder, elements).into()
+}
+
+/// Builds a Move vector of `elements` without `drop`. The vector is tracked as a result without
+/// `drop`, as it must be used by a later command.
+#[must_use = "vectors of values without `drop` must be used by a later command"]
+pub fn make_tracked_move_vec<T: MoveType>(
+    builder: &mut impl PtbBuilder,
+    elements: Vec<Arg<T>>,
+) -> Arg<Vec<T>> {
+    let result = add_make_move_vec(builder, elements);
+    builder.track_result(&result);
+    result.into()
+}
+
+fn add_make_move_vec<T: MoveType>(
+    builder: &mut impl PtbBuilder,
+    elements: Vec<Arg<T>>,
+) -> Argument {
+    let elements = elements
+        .into_iter()
+        .map(|element| element.consume(builder))
+        .collect();
+    builder.builder().make_move_vec(Some(T::type_()), elements)
+}
+
 pub trait ToInput {
     fn to_input(&self) -> Input;
 }
@@ -1,6 +1,13 @@
+pub mod abilities;
+pub mod abort;
+pub mod builder;
+pub mod coin;
+pub mod error;
 pub mod functions;
 
+pub use bcs;
 pub use move_core_types::u256::U256;
+use serde::de::DeserializeOwned;
 use serde::Serialize;
 use std::str::FromStr;
 pub use sui_sdk_types::Address;
@@ -73,6 +80,7 @@ impl MoveType for bool {
         TypeTag::Bool
     }
 }
+/// `ObjectId` stands for both `0x2::object::UID` and `0x2::object::ID`, and is typed as `UID`.
 impl MoveType for ObjectId {
     fn type_() -> TypeTag {
         TypeTag::Struct(Box::new(StructTag {
@@ -121,3 +129,18 @@ impl<T: MoveType> MoveType for Vec<T> {
 pub trait Key: MoveStruct {
     fn id(&self) -> &ObjectId;
 }
+
+/// Object of a generic type, decoded as a given instantiation of the type with [`Self::decode`].
+#[derive(Clone, Debug)]
+pub struct GenericObject {
+    pub type_: StructTag,
+    pub contents: Vec<u8>,
+}
+
+impl GenericObject {
+    /// Decodes the object as `T`, returns `None` if the object is of another type, e.g. another
+    /// instantiation of the same generic type.
+    pub fn decode<T: MoveStruct + DeserializeOwned>(&self) -> Option<Result<T, bcs::Error>> {
+        (T::struct_type() == self.type_).then(|| bcs::from_bytes(&self.contents))
+    }
+}
@@ -0,0 +1,24 @@
+use move_types::abort::AbortCode;
+
+#[test]
+fn test_plain_abort_code() {
+    assert_eq!(AbortCode::from(0), AbortCode::Code(0));
+    assert_eq!(AbortCode::from(13), AbortCode::Code(13));
+    assert_eq!(
+        AbortCode::from(u64::MAX >> 1),
+        AbortCode::Code(u64::MAX >> 1)
+    );
+}
+
+#[test]
+fn test_clever_abort_code() {
+    let code = (1 << 63) | (42 << 32) | (7 << 16) | 3;
+    assert_eq!(
+        AbortCode::from(code),
+        AbortCode::Clever {
+            line: 42,
+            identifier_index: 7,
+            constant_index: 3,
+        }
+    );
+}
@@ -0,0 +1,49 @@
+use move_types::builder::CheckedBuilder;
+use move_types::error::BindingError;
+use move_types::functions::{make_move_vec, make_tracked_move_vec, Arg};
+use move_types::MoveStruct;
+use serde::Serialize;
+use std::str::FromStr;
+use sui_sdk_types::{Address, Argument, Identifier, StructTag};
+use sui_transaction_builder::TransactionBuilder;
+
+/// Stands for a struct without `drop`, like a flash loan receipt.
+#[derive(Serialize)]
+struct Receipt {
+    amount: u64,
+}
+
+impl MoveStruct for Receipt {
+    fn struct_type() -> StructTag {
+        StructTag {
+            address: Address::TWO,
+            module: Identifier::from_str("loan").unwrap(),
+            name: Identifier::from_str("Receipt").unwrap(),
+            type_params: vec![],
+        }
+    }
+}
+
+#[test]
+fn test_make_move_vec_tracking() {
+    let mut builder = CheckedBuilder::new(TransactionBuilder::new());
+    let receipts = make_tracked_move_vec(&mut builder, vec![Arg::from(Receipt { amount: 1 })]);
+    assert_eq!(builder.unused_results(), [Argument::Result(0)]);
+
+    // Using the vector by value consumes it.
+    let _receipts = make_tracked_move_vec(&mut builder, vec![receipts]);
+    assert_eq!(builder.unused_results(), [Argument::Result(1)]);
+
+    let _values = make_move_vec::<u64>(&mut builder, vec![1.into(), 2.into()]);
+    assert_eq!(builder.unused_results(), [Argument::Result(1)]);
+}
+
+#[test]
+fn test_checked_builder_finish_unused_results() {
+    let mut builder = CheckedBuilder::new(TransactionBuilder::new());
+    let _receipts = make_tracked_move_vec(&mut builder, vec![Arg::from(Receipt { amount: 1 })]);
+    assert!(matches!(
+        builder.finish(),
+        Err(BindingError::UnusedResults(unused)) if unused == [Argument::Result(0)]
+    ));
+}
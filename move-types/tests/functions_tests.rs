@@ -0,0 +1,155 @@
+use move_types::error::BindingError;
+use move_types::functions::{Arg, MutRef, Receiving, Ref};
+use move_types::{Key, MoveStruct};
+use serde::{Serialize, Serializer};
+use std::str::FromStr;
+use sui_sdk_types::{Address, Argument, Identifier, ObjectDigest, ObjectId, StructTag};
+use sui_transaction_builder::unresolved::Input;
+
+struct Counter {
+    id: ObjectId,
+}
+
+impl Serialize for Counter {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.id.serialize(serializer)
+    }
+}
+
+impl MoveStruct for Counter {
+    fn struct_type() -> StructTag {
+        struct_tag("Counter")
+    }
+}
+
+impl Key for Counter {
+    fn id(&self) -> &ObjectId {
+        &self.id
+    }
+}
+
+/// Stands for `0x2::transfer::Receiving<Counter>`.
+struct ReceivingCounter;
+
+impl Serialize for ReceivingCounter {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_unit()
+    }
+}
+
+impl MoveStruct for ReceivingCounter {
+    fn struct_type() -> StructTag {
+        struct_tag("Receiving")
+    }
+}
+
+impl Receiving for ReceivingCounter {}
+
+fn struct_tag(name: &str) -> StructTag {
+    StructTag {
+        address: Address::TWO,
+        module: Identifier::from_str("counter").unwrap(),
+        name: Identifier::from_str(name).unwrap(),
+        type_params: vec![],
+    }
+}
+
+fn object() -> Input {
+    Input::owned(ObjectId::new([1; 32]), 1, ObjectDigest::new([2; 32]))
+}
+
+#[test]
+fn test_resolved_arg_to_argument() {
+    let arg = Arg::<u64>::from(Argument::Result(3));
+    assert_eq!(Argument::try_from(arg).unwrap(), Argument::Result(3));
+    let arg = Ref::<u64>::from(Argument::NestedResult(3, 1));
+    assert_eq!(
+        Argument::try_from(arg).unwrap(),
+        Argument::NestedResult(3, 1)
+    );
+    let arg = MutRef::<u64>::from(Argument::Input(0));
+    assert_eq!(Argument::try_from(arg).unwrap(), Argument::Input(0));
+}
+
+#[test]
+fn test_unresolved_arg_to_argument() {
+    let value = 1u64;
+    let mut mut_value = 1u64;
+
+    assert!(matches!(
+        Argument::try_from(Arg::Raw(1u64)),
+        Err(BindingError::UnresolvedArg)
+    ));
+    assert!(matches!(
+        Argument::try_from(Arg::<u64>::Object(object())),
+        Err(BindingError::UnresolvedArg)
+    ));
+    assert!(matches!(
+        Argument::try_from(Ref::Raw(&value)),
+        Err(BindingError::UnresolvedArg)
+    ));
+    assert!(matches!(
+        Argument::try_from(Ref::<u64>::Object(object())),
+        Err(BindingError::UnresolvedArg)
+    ));
+    assert!(matches!(
+        Argument::try_from(MutRef::Raw(&mut mut_value)),
+        Err(BindingError::UnresolvedArg)
+    ));
+    assert!(matches!(
+        Argument::try_from(MutRef::<u64>::Object(object())),
+        Err(BindingError::UnresolvedArg)
+    ));
+}
+
+/// Debug representation of the object input of `arg`, `Input` has no other public view.
+fn input<T>(arg: Arg<T>) -> String {
+    match arg {
+        Arg::Object(input) => format!("{input:?}"),
+        _ => panic!("expected an object arg"),
+    }
+}
+
+#[test]
+fn test_object_arg_inputs() {
+    let id = ObjectId::new([1; 32]);
+    let digest = ObjectDigest::new([2; 32]);
+    let expected = |input: Input| format!("{input:?}");
+
+    assert_eq!(
+        input(Arg::<Counter>::owned(id, 3, digest)),
+        expected(Input::owned(id, 3, digest))
+    );
+    assert_eq!(
+        input(Arg::<Counter>::shared(id, 3)),
+        expected(Input::shared(id, 3, true))
+    );
+    assert_eq!(
+        input(Arg::<ReceivingCounter>::receiving(id, 3, digest)),
+        expected(Input::receiving(id, 3, digest))
+    );
+    assert_ne!(
+        expected(Input::shared(id, 3, true)),
+        expected(Input::shared(id, 3, false))
+    );
+
+    let Ref::Object(shared) = Ref::<Counter>::shared(id, 3, false) else {
+        panic!("expected an object ref");
+    };
+    assert_eq!(format!("{shared:?}"), expected(Input::shared(id, 3, false)));
+    let Ref::Object(immutable) = Ref::<Counter>::immutable(id, 3, digest) else {
+        panic!("expected an object ref");
+    };
+    assert_eq!(
+        format!("{immutable:?}"),
+        expected(Input::immutable(id, 3, digest))
+    );
+    let MutRef::Object(shared) = MutRef::<Counter>::shared(id, 3) else {
+        panic!("expected an object ref");
+    };
+    assert_eq!(format!("{shared:?}"), expected(Input::shared(id, 3, true)));
+    let MutRef::Object(owned) = MutRef::<Counter>::owned(id, 3, digest) else {
+        panic!("expected an object ref");
+    };
+    assert_eq!(format!("{owned:?}"), expected(Input::owned(id, 3, digest)));
+}
@@ -0,0 +1,35 @@
+use move_types::functions::{transfer_objects, Arg};
+use move_types::{Key, MoveStruct};
+use serde::Serialize;
+use std::str::FromStr;
+use sui_sdk_types::{Address, Argument, Identifier, ObjectId, StructTag};
+use sui_transaction_builder::TransactionBuilder;
+
+/// Stands for an object with `key` only, which cannot be transferred by a transaction command.
+#[derive(Serialize)]
+struct Clock {
+    id: ObjectId,
+}
+
+impl MoveStruct for Clock {
+    fn struct_type() -> StructTag {
+        StructTag {
+            address: Address::TWO,
+            module: Identifier::from_str("clock").unwrap(),
+            name: Identifier::from_str("Clock").unwrap(),
+            type_params: vec![],
+        }
+    }
+}
+
+impl Key for Clock {
+    fn id(&self) -> &ObjectId {
+        &self.id
+    }
+}
+
+fn main() {
+    let mut builder = TransactionBuilder::new();
+    let clock = Arg::<Clock>::Resolved(Argument::Input(0));
+    transfer_objects(&mut builder, vec![clock], Address::TWO.into());
+}
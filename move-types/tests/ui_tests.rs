@@ -0,0 +1,5 @@
+#[test]
+fn test_ability_bounds() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}